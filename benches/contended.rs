@@ -0,0 +1,35 @@
+//! Several threads registering and notifying on one event at once, which is where the chain lock
+//! is contended. Only meaningful with at least as many cores as threads, on fewer it mostly
+//! measures the uncontended path plus scheduling.
+//!
+//! `cargo bench --bench contended`
+
+use std::time::Instant;
+use wake_me::Event;
+
+const THREADS: u32 = 8;
+const ITERATIONS: u32 = 100_000;
+
+fn main() {
+    let event = Event::default();
+    let start = Instant::now();
+    std::thread::scope(|s| {
+        for _ in 0..THREADS {
+            s.spawn(|| {
+                for _ in 0..ITERATIONS {
+                    let guard = event.listen();
+                    event.notify_one();
+                    // another thread's notify may have taken ours, don't park on it
+                    drop(guard);
+                }
+            });
+        }
+    });
+    let elapsed = start.elapsed();
+    println!(
+        "contended listen / notify: {:?}/iter ({} threads x {} iterations)",
+        elapsed / (THREADS * ITERATIONS),
+        THREADS,
+        ITERATIONS
+    );
+}
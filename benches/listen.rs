@@ -0,0 +1,24 @@
+//! Cost of registering and completing a listener on a single thread, with no parking involved.
+//!
+//! `cargo bench --bench listen`
+
+use std::time::Instant;
+use wake_me::Event;
+
+const ITERATIONS: u32 = 1_000_000;
+
+fn main() {
+    let event = Event::default();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        let guard = event.listen();
+        event.notify_one();
+        guard.wait();
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "listen / notify / wait: {:?}/iter ({} iterations)",
+        elapsed / ITERATIONS,
+        ITERATIONS
+    );
+}
@@ -0,0 +1,51 @@
+//! Round trip latency of a listen / notify hand off between two threads.
+//!
+//! Compare the std and parking_lot_core paths with
+//! `cargo bench --bench park` and `cargo bench --bench park --features parking-lot`.
+
+use portable_atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+use wake_me::Event;
+
+const ITERATIONS: u32 = 100_000;
+
+fn main() {
+    let ping = Event::default();
+    let pong = Event::default();
+    let ping_flag = AtomicBool::new(false);
+    let pong_flag = AtomicBool::new(false);
+
+    let start = Instant::now();
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            for _ in 0..ITERATIONS {
+                while !ping_flag.swap(false, Ordering::AcqRel) {
+                    let guard = ping.listen();
+                    if ping_flag.swap(false, Ordering::AcqRel) {
+                        break;
+                    }
+                    guard.wait();
+                }
+                pong_flag.store(true, Ordering::Release);
+                pong.notify_one();
+            }
+        });
+        for _ in 0..ITERATIONS {
+            ping_flag.store(true, Ordering::Release);
+            ping.notify_one();
+            while !pong_flag.swap(false, Ordering::AcqRel) {
+                let guard = pong.listen();
+                if pong_flag.swap(false, Ordering::AcqRel) {
+                    break;
+                }
+                guard.wait();
+            }
+        }
+    });
+    let elapsed = start.elapsed();
+    println!(
+        "park round trip: {:?}/iter ({} iterations)",
+        elapsed / ITERATIONS,
+        ITERATIONS
+    );
+}
@@ -0,0 +1,88 @@
+//! Lets a [`WaitGuard`] from the sync `listen` be awaited. Polling stores the task in the slot the
+//! guard shares with its wakers, whichever of them finishes the wait wakes the task too.
+
+use crate::waker::acknowledge;
+use crate::{State, WaitGuard};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+/// Resolves to the state the guard finished in once it stops waiting, so `listen().await` works
+/// like [`Event::notified`](crate::Event::notified). The guard keeps its registration across
+/// polls from different tasks, only the task polled most recently is woken.
+///
+/// ```
+/// # use wake_me::{Event, State};
+/// # smol::block_on(async {
+/// let event = Event::default();
+/// let guard = event.listen();
+/// event.notify_one();
+/// assert_eq!(guard.await, State::Notified);
+/// # });
+/// ```
+impl Future for WaitGuard {
+    type Output = State;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<State> {
+        if self.get_state() == State::Waiting && self.register_task(cx.waker()) {
+            return Poll::Pending;
+        }
+        acknowledge(self.shared_state());
+        Poll::Ready(self.get_state())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Event, State};
+    use core::future::{poll_fn, Future};
+    use core::pin::Pin;
+    use core::task::Poll;
+    use std::sync::Arc;
+    use std::task::Wake;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn await_guard() {
+        let event = Arc::new(Event::default());
+        let guard = event.listen();
+        let notifier = event.clone();
+        let jh = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            notifier.notify_one();
+        });
+        assert_eq!(guard.await, State::Notified);
+        jh.await.expect("notifier panicked");
+
+        let guard = event.listen();
+        event.close();
+        assert_eq!(guard.await, State::Closed);
+    }
+
+    struct Task;
+
+    impl Wake for Task {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    #[tokio::test]
+    async fn dropped_before_wake() {
+        let event = Event::default();
+        let task = Arc::new(Task);
+        let mut guard = event.listen();
+        let waker = task.clone().into();
+        let mut cx = core::task::Context::from_waker(&waker);
+        assert!(Pin::new(&mut guard).poll(&mut cx).is_pending());
+        assert_eq!(Arc::strong_count(&task), 3);
+        drop(guard);
+        // the task isn't kept alive by the registration left behind
+        assert_eq!(Arc::strong_count(&task), 2);
+        assert_eq!(event.notify_all(), 0);
+
+        let mut guard = event.listen();
+        let polled = poll_fn(|cx| Poll::Ready(Pin::new(&mut guard).poll(cx))).await;
+        assert!(polled.is_pending());
+        event.notify_one();
+        assert_eq!(guard.await, State::Notified);
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{Event, WaitGuard};
+use core::task::{Context, Poll};
+
+/// Coalesces bursts of notifications on an [`Event`] into a single wakeup carrying a count.
+#[derive(Debug)]
+pub struct BatchListener<'a, T = ()> {
+    event: &'a Event<T>,
+    last_seen: usize,
+    registration: Option<(WaitGuard, core::task::Waker)>,
+}
+
+impl<'a, T> BatchListener<'a, T> {
+    pub(crate) fn new(event: &'a Event<T>) -> Self {
+        Self {
+            event,
+            last_seen: event.notify_count(),
+            registration: None,
+        }
+    }
+
+    /// Resolves with the number of notify calls made on the event since the previous batch
+    /// (or since the listener was created).
+    pub async fn notified_batch(&mut self) -> usize {
+        core::future::poll_fn(|cx| self.poll_batch(cx)).await
+    }
+
+    pub fn poll_batch(&mut self, cx: &mut Context<'_>) -> Poll<usize> {
+        if let Some(count) = self.take_batch() {
+            return Poll::Ready(count);
+        }
+        let registered = crate::listener::update_registration(&mut self.registration, cx)
+            .is_some_and(|state| state.is_pending());
+        if !registered {
+            let guard = self.event.listen_async(cx.waker().clone());
+            self.registration = Some((guard, cx.waker().clone()));
+        }
+        // a notify between the first check and registering wouldn't have seen us in the chain
+        match self.take_batch() {
+            Some(count) => Poll::Ready(count),
+            None => Poll::Pending,
+        }
+    }
+
+    fn take_batch(&mut self) -> Option<usize> {
+        let current = self.event.notify_count();
+        if current == self.last_seen {
+            return None;
+        }
+        let count = current.wrapping_sub(self.last_seen);
+        self.last_seen = current;
+        self.registration = None;
+        Some(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn coalesces_burst() {
+        let event = Event::default();
+        let mut batch = event.batched();
+        event.notify_one();
+        event.notify_one();
+        event.notify_one();
+        assert_eq!(batch.notified_batch().await, 3);
+        event.notify_all();
+        assert_eq!(batch.notified_batch().await, 1);
+    }
+
+    #[tokio::test]
+    async fn wakes_pending_batch() {
+        let event = Arc::new(Event::default());
+        let mut batch = event.batched();
+        let notifier = event.clone();
+        let jh = tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            notifier.notify_one();
+        });
+        assert_eq!(batch.notified_batch().await, 1);
+        jh.await.expect("notifier panicked");
+    }
+}
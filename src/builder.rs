@@ -0,0 +1,203 @@
+use crate::Event;
+use portable_atomic::AtomicBool;
+
+/// Which waiting listener [`Event::notify_one`] wakes first, the event's fairness policy.
+/// Listeners from [`Event::listen_with_priority`] are ordered by priority first.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum WakeOrder {
+    /// Strictly fair: the listener that has been waiting the longest. Listeners are queued in the
+    /// order their `listen` calls took the chain lock and woken in exactly that order, so nobody
+    /// is overtaken by a later listener. Suitable for building fair locks.
+    #[default]
+    Fifo,
+    /// Throughput over fairness: the listener that registered most recently, whose thread is the
+    /// most likely to still be running or have a warm cache. A listener can be overtaken
+    /// indefinitely while newer ones keep arriving.
+    Lifo,
+}
+
+/// Configures an [`Event`], see [`Event::builder`].
+///
+/// ```
+/// # use wake_me::{Event, WakeOrder};
+/// let event: Event = Event::builder()
+///     .bounded(2)
+///     .wake_order(WakeOrder::Lifo)
+///     .spin(100)
+///     .build();
+/// let _a = event.listen();
+/// let _b = event.listen();
+/// assert!(event.try_listen().is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct EventBuilder {
+    capacity: usize,
+    order: WakeOrder,
+    spins: u32,
+    auto_compact: usize,
+}
+
+impl Default for EventBuilder {
+    fn default() -> Self {
+        Self {
+            capacity: usize::MAX,
+            order: WakeOrder::Fifo,
+            spins: 0,
+            auto_compact: 0,
+        }
+    }
+}
+
+impl EventBuilder {
+    /// Limits the event to `capacity` registered listeners. Registering past that fails, see
+    /// [`Event::try_listen`].
+    pub fn bounded(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Lets any number of listeners register, the default.
+    pub fn unbounded(mut self) -> Self {
+        self.capacity = usize::MAX;
+        self
+    }
+
+    pub fn wake_order(mut self, order: WakeOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Has [`WaitGuard::wait`](crate::WaitGuard::wait) spin `spins` times waiting for a
+    /// notification before parking the thread. Defaults to 0, parking straight away.
+    pub fn spin(mut self, spins: u32) -> Self {
+        self.spins = spins;
+        self
+    }
+
+    /// See [`Event::with_auto_compact`].
+    pub fn auto_compact(mut self, every: usize) -> Self {
+        self.auto_compact = every;
+        self
+    }
+
+    /// Builds the event, `T` is the payload its notifications can carry, see
+    /// [`Event::with_payload`].
+    pub fn build<T>(self) -> Event<T> {
+        Event {
+            capacity: self.capacity,
+            lifo: AtomicBool::new(self.order == WakeOrder::Lifo),
+            spins: self.spins,
+            auto_compact: self.auto_compact,
+            ..Event::with_payload()
+        }
+    }
+}
+
+impl Event {
+    pub fn builder() -> EventBuilder {
+        EventBuilder::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ListenError, State};
+
+    #[test]
+    fn bounded_rejects_when_full() {
+        let event: Event = Event::builder().bounded(1).build();
+        let first = event.try_listen().expect("room for one");
+        assert_eq!(event.try_listen().err(), Some(ListenError::Full));
+        assert_eq!(event.listen().get_state(), State::Full);
+        assert_eq!(event.listener_count(), 1);
+        event.notify_one();
+        assert_eq!(first.get_state(), State::Notified);
+        assert!(event.try_listen().is_ok());
+    }
+
+    #[test]
+    fn bounded_sweeps_dropped() {
+        let event = Event::bounded(2);
+        assert_eq!(event.capacity(), Some(2));
+        assert_eq!(Event::default().capacity(), None);
+        let _live = event.listen();
+        drop(event.listen());
+        let guard = event.try_listen().expect("dropped listener frees its slot");
+        assert_eq!(event.try_listen().err(), Some(ListenError::Full));
+        assert_eq!(event.notify_all(), 2);
+        assert_eq!(guard.get_state(), State::Notified);
+    }
+
+    #[tokio::test]
+    async fn bounded_async_listener_full() {
+        let event = Event::bounded(0);
+        assert_eq!(event.notified().await, State::Full);
+    }
+
+    #[test]
+    fn fifo_is_strict_across_threads() {
+        let event: &Event = &Event::builder().wake_order(WakeOrder::Fifo).build();
+        let (sender, recv) = std::sync::mpsc::channel();
+        std::thread::scope(|s| {
+            for i in 0..8 {
+                let sender = sender.clone();
+                s.spawn(move || {
+                    let guard = event.listen();
+                    guard.wait();
+                    sender.send(i).expect("receiver is alive");
+                });
+                // registered one at a time so the chain order is known
+                event.wait_for_listeners(i + 1);
+            }
+            for i in 0..8 {
+                assert!(event.notify_one());
+                assert_eq!(recv.recv().expect("a listener was woken"), i);
+            }
+        });
+    }
+
+    #[test]
+    fn lifo_wakes_newest_first() {
+        let event: Event = Event::builder().wake_order(WakeOrder::Lifo).build();
+        let old = event.listen();
+        let new = event.listen();
+        event.notify_one();
+        assert_eq!(new.get_state(), State::Notified);
+        assert_eq!(old.get_state(), State::Waiting);
+    }
+
+    #[test]
+    fn wake_order_switched_at_runtime() {
+        let event = Event::default();
+        assert_eq!(event.wake_order(), WakeOrder::Fifo);
+        let guards: Vec<_> = (0..3).map(|_| event.listen()).collect();
+        event.set_wake_order(WakeOrder::Lifo);
+        event.notify_one();
+        assert_eq!(guards[2].get_state(), State::Notified);
+        event.set_wake_order(WakeOrder::Fifo);
+        event.notify_one();
+        assert_eq!(guards[0].get_state(), State::Notified);
+        assert_eq!(guards[1].get_state(), State::Waiting);
+    }
+
+    #[test]
+    fn spinning_waiter_is_woken() {
+        let event: Event = Event::builder().spin(1_000).build();
+        std::thread::scope(|s| {
+            let guard = event.listen();
+            s.spawn(|| event.notify_one());
+            guard.wait();
+            assert_eq!(guard.get_state(), State::Notified);
+        });
+    }
+
+    #[test]
+    fn builds_payload_event() {
+        let event = Event::builder().bounded(1).build::<u32>();
+        assert_eq!(event.capacity(), Some(1));
+        let guard = event.listen();
+        assert_eq!(event.notify_one_with(5), Ok(()));
+        assert_eq!(guard.wait_value(&event), Some(5));
+    }
+}
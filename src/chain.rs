@@ -0,0 +1,94 @@
+use crate::pad::CachePadded;
+use crate::waker::Waker;
+use portable_atomic::AtomicUsize;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError};
+
+/// The listeners registered with an event. Guards keep a reference to it so a guard dropped while
+/// still waiting can unlink itself, even once the event is gone.
+///
+/// A locked deque rather than a lock free queue since listeners leave from the middle too (unlink,
+/// sweeps, priorities, LIFO wakes).
+#[derive(Debug, Default)]
+pub(crate) struct Chain {
+    pub(crate) wakers: CachePadded<Mutex<VecDeque<Waker>>>,
+    /// Written by listeners and read by notifiers before they take the chain lock.
+    pub(crate) len: CachePadded<AtomicUsize>,
+    /// Listeners from [`Event::listen_with_key`](crate::Event::listen_with_key), indexed by key
+    /// rather than queued.
+    pub(crate) keyed: Mutex<BTreeMap<u64, Vec<Waker>>>,
+}
+
+impl Chain {
+    pub(crate) fn lock(&self) -> MutexGuard<'_, VecDeque<Waker>> {
+        // wakers are never invoked while the lock is held so a poisoned chain is still consistent
+        self.wakers.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Removes every waker for the listener identified by `key`.
+    pub(crate) fn unlink(&self, key: usize) {
+        let mut wakers = self.lock();
+        let before = wakers.len();
+        wakers.retain(|node| node.key() != key);
+        let removed = before - wakers.len();
+        self.len.fetch_sub(removed, Ordering::Release);
+    }
+
+    /// Switches the queued listener identified by `id` over to waking `waker`, `false` if it
+    /// isn't queued.
+    pub(crate) fn set_async(&self, id: usize, waker: &core::task::Waker) -> bool {
+        let mut wakers = self.lock();
+        match wakers.iter_mut().find(|node| node.key() == id) {
+            Some(node) => {
+                node.set_async(waker);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub(crate) fn keyed(&self) -> MutexGuard<'_, BTreeMap<u64, Vec<Waker>>> {
+        self.keyed.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Removes the keyed listener identified by `id` from the `key` bucket, and the bucket with it
+    /// once it's empty.
+    pub(crate) fn unlink_keyed(&self, key: u64, id: usize) {
+        let mut keyed = self.keyed();
+        if let Some(listeners) = keyed.get_mut(&key) {
+            listeners.retain(|node| node.key() != id);
+            if listeners.is_empty() {
+                keyed.remove(&key);
+            }
+        }
+    }
+}
+
+/// An event's [`Chain`], allocated by the first listener so events can still be created in a
+/// `const` context.
+#[derive(Debug, Default)]
+pub(crate) struct ChainCell(OnceLock<Arc<Chain>>);
+
+impl ChainCell {
+    pub(crate) const fn new() -> Self {
+        Self(OnceLock::new())
+    }
+
+    pub(crate) fn get(&self) -> &Arc<Chain> {
+        self.0.get_or_init(Arc::default)
+    }
+}
+
+impl Drop for ChainCell {
+    fn drop(&mut self) {
+        // guards may keep the chain alive, whoever is still waiting finds out the event is gone
+        if let Some(chain) = self.0.get() {
+            let wakers = core::mem::take(&mut *chain.lock());
+            chain.len.store(0, Ordering::Release);
+            let keyed = core::mem::take(&mut *chain.keyed());
+            drop(wakers);
+            drop(keyed);
+        }
+    }
+}
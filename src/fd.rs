@@ -0,0 +1,92 @@
+use crate::waker::Waker;
+use crate::{Event, WaitGuard};
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+impl<T> Event<T> {
+    /// Listens with a file descriptor that becomes readable once the listener is woken, so that
+    /// a reactor (epoll, kqueue, mio...) can wait on the event alongside its other sources.
+    ///
+    /// This is an `eventfd` on Linux and the read end of a self-pipe elsewhere, both non-blocking.
+    /// The descriptor is owned by the caller and isn't drained by the event.
+    pub fn listen_fd(&self) -> io::Result<(WaitGuard, OwnedFd)> {
+        let (read, write) = signal_fds()?;
+        let (waker, guard) = Waker::new_custom(move || signal(&write));
+        Ok((self.register(waker, guard), read))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn signal_fds() -> io::Result<(OwnedFd, OwnedFd)> {
+    // SAFETY: plain syscall, the returned descriptor is checked before taking ownership of it
+    let fd = unsafe { libc::eventfd(0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: fd was just created and isn't owned by anything else
+    let read = unsafe { OwnedFd::from_raw_fd(fd) };
+    let write = read.try_clone()?;
+    Ok((read, write))
+}
+
+#[cfg(target_os = "linux")]
+fn signal(fd: &OwnedFd) {
+    let value: u64 = 1;
+    // SAFETY: writes 8 bytes from a live u64. A full counter (EAGAIN) is already readable
+    unsafe { libc::write(fd.as_raw_fd(), (&value as *const u64).cast(), 8) };
+}
+
+#[cfg(not(target_os = "linux"))]
+fn signal_fds() -> io::Result<(OwnedFd, OwnedFd)> {
+    let mut fds = [0; 2];
+    // SAFETY: fds has room for the two descriptors pipe writes
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: both descriptors were just created and aren't owned by anything else
+    let (read, write) = unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) };
+    for fd in [&read, &write] {
+        // SAFETY: fcntl on descriptors we own
+        let ok = unsafe {
+            libc::fcntl(fd.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC) >= 0
+                && libc::fcntl(fd.as_raw_fd(), libc::F_SETFL, libc::O_NONBLOCK) >= 0
+        };
+        if !ok {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok((read, write))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn signal(fd: &OwnedFd) {
+    // SAFETY: writes a single byte. A full pipe (EAGAIN) is already readable
+    unsafe { libc::write(fd.as_raw_fd(), [1u8].as_ptr().cast(), 1) };
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::State;
+
+    fn readable(fd: &OwnedFd) -> bool {
+        let mut poll = libc::pollfd {
+            fd: fd.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: polls a single live pollfd without blocking
+        let ready = unsafe { libc::poll(&mut poll, 1, 0) };
+        ready == 1 && poll.revents & libc::POLLIN != 0
+    }
+
+    #[test]
+    fn fd_readable_on_notify() {
+        let event = Event::default();
+        let (guard, fd) = event.listen_fd().expect("couldn't create fd");
+        assert!(!readable(&fd));
+        event.notify_one();
+        assert!(readable(&fd));
+        assert_eq!(guard.get_state(), State::Notified);
+    }
+}
@@ -0,0 +1,61 @@
+use crate::Event;
+use portable_atomic::{AtomicBool, Ordering};
+
+/// A one way latch, every [`Gate::wait`] blocks until [`Gate::open`] is called and passes
+/// straight through after that.
+#[derive(Debug, Default)]
+pub struct Gate {
+    open: AtomicBool,
+    event: Event,
+}
+
+impl Gate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opens the gate for good, waking everyone waiting on it. Opening it again does nothing.
+    pub fn open(&self) {
+        if !self.open.swap(true, Ordering::SeqCst) {
+            self.event.notify_all();
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open.load(Ordering::SeqCst)
+    }
+
+    pub fn wait(&self) {
+        if self.is_open() {
+            return;
+        }
+        let guard = self.event.listen();
+        // checked again after listening, an open in between wouldn't have seen us
+        if self.is_open() {
+            return;
+        }
+        guard.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn waiters_pass_once_open() {
+        let gate = &Gate::new();
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(move || gate.wait());
+            }
+            std::thread::sleep(Duration::from_millis(10));
+            gate.open();
+            gate.open();
+        });
+        assert!(gate.is_open());
+        gate.wait();
+        assert!(gate.event.chain().is_empty());
+    }
+}
@@ -0,0 +1,95 @@
+use crate::Event;
+use std::sync::Arc;
+
+/// An owned, cloneable handle to an [`Event`], so producers and consumers in different threads or
+/// tasks can each hold one without wrapping the event in an `Arc` themselves. Every clone refers
+/// to the same event and the whole [`Event`] API is available through deref.
+///
+/// ```
+/// # use wake_me::{EventHandle, State};
+/// let handle = EventHandle::new();
+/// let notifier = handle.clone();
+/// let guard = handle.listen();
+/// std::thread::spawn(move || notifier.notify_one());
+/// guard.wait();
+/// assert_eq!(guard.get_state(), State::Notified);
+/// ```
+#[derive(Debug)]
+pub struct EventHandle<T = ()> {
+    event: Arc<Event<T>>,
+}
+
+impl EventHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Default for EventHandle {
+    fn default() -> Self {
+        Event::new().into()
+    }
+}
+
+impl<T> Clone for EventHandle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            event: self.event.clone(),
+        }
+    }
+}
+
+impl<T> From<Event<T>> for EventHandle<T> {
+    fn from(event: Event<T>) -> Self {
+        Self {
+            event: Arc::new(event),
+        }
+    }
+}
+
+impl<T> core::ops::Deref for EventHandle<T> {
+    type Target = Event<T>;
+
+    fn deref(&self) -> &Event<T> {
+        &self.event
+    }
+}
+
+impl<T> AsRef<Event<T>> for EventHandle<T> {
+    fn as_ref(&self) -> &Event<T> {
+        &self.event
+    }
+}
+
+impl<T> EventHandle<T> {
+    /// Whether both handles refer to the same event.
+    pub fn same_event(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.event, &other.event)
+    }
+}
+
+impl<T> Event<T> {
+    pub fn into_handle(self) -> EventHandle<T> {
+        self.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::State;
+
+    #[tokio::test]
+    async fn handles_across_tasks() {
+        let handle = Event::builder().build().into_handle();
+        let producer = handle.clone();
+        assert!(producer.same_event(&handle));
+        assert!(!producer.same_event(&EventHandle::new()));
+        let jh = tokio::spawn(async move {
+            producer.wait_for_listeners_async(1).await;
+            producer.notify_all()
+        });
+        assert_eq!(handle.notified().await, State::Notified);
+        assert_eq!(jh.await.expect("producer panicked"), 1);
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{Event, SharedEvent};
+use std::sync::{Arc, MutexGuard, PoisonError, Weak};
+
+impl Event {
+    /// Creates an event under `parent`, so that [`Event::notify_all`] on the parent (or any of its
+    /// ancestors) also wakes everyone waiting on the child, e.g. to shut down everything under a
+    /// subsystem. Notifying the child doesn't touch the parent. The parent only holds on to the
+    /// child weakly.
+    ///
+    /// ```
+    /// # use wake_me::{Event, State};
+    /// let subsystem = Event::default();
+    /// let worker = Event::new_child(&subsystem);
+    /// let guard = worker.listen();
+    /// subsystem.notify_all();
+    /// assert_eq!(guard.get_state(), State::Notified);
+    /// ```
+    pub fn new_child(parent: &Event) -> SharedEvent {
+        let child = SharedEvent::default();
+        let mut children = parent.children();
+        // a good time to forget children that are gone
+        children.retain(|child| child.strong_count() != 0);
+        children.push(Arc::downgrade(&child));
+        child
+    }
+}
+
+impl<T> Event<T> {
+    fn children(&self) -> MutexGuard<'_, Vec<Weak<Event>>> {
+        self.children.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Calls [`Event::notify_all`] on every child that is still alive, returning how many
+    /// listeners they woke between them.
+    pub(crate) fn notify_children(&self) -> usize {
+        let children: Vec<_> = {
+            let mut children = self.children();
+            if children.is_empty() {
+                return 0;
+            }
+            children.retain(|child| child.strong_count() != 0);
+            children.iter().filter_map(Weak::upgrade).collect()
+        };
+        // notified outside the lock, a child's own children are handled by its notify_all
+        children.iter().map(|child| child.notify_all()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::State;
+
+    #[test]
+    fn cascades_to_descendants() {
+        let root = Event::default();
+        let child = Event::new_child(&root);
+        let grandchild = Event::new_child(&child);
+        let guards = [root.listen(), child.listen(), grandchild.listen()];
+        assert_eq!(root.notify_all(), 3);
+        assert!(guards.iter().all(|g| g.get_state() == State::Notified));
+    }
+
+    #[test]
+    fn child_notify_stays_local() {
+        let root = Event::default();
+        let child = Event::new_child(&root);
+        let sibling = Event::new_child(&root);
+        let parent_guard = root.listen();
+        let sibling_guard = sibling.listen();
+        let guard = child.listen();
+        assert_eq!(child.notify_all(), 1);
+        assert_eq!(guard.get_state(), State::Notified);
+        assert_eq!(parent_guard.get_state(), State::Waiting);
+        assert_eq!(sibling_guard.get_state(), State::Waiting);
+    }
+
+    #[test]
+    fn dropped_children_forgotten() {
+        let root = Event::default();
+        drop(Event::new_child(&root));
+        let child = Event::new_child(&root);
+        assert_eq!(root.children().len(), 1);
+        drop(child);
+        assert_eq!(root.notify_all(), 0);
+        assert!(root.children().is_empty());
+    }
+}
@@ -0,0 +1,41 @@
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// A user supplied callback that can be swapped at runtime through a shared reference.
+pub(crate) struct Hook<F: ?Sized> {
+    inner: Mutex<Option<Arc<F>>>,
+}
+
+impl<F: ?Sized> Default for Hook<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: ?Sized> core::fmt::Debug for Hook<F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.get() {
+            Some(_) => write!(f, "Hook(set)"),
+            None => write!(f, "Hook(unset)"),
+        }
+    }
+}
+
+impl<F: ?Sized> Hook<F> {
+    pub const fn new() -> Self {
+        Self {
+            inner: Mutex::new(None),
+        }
+    }
+
+    pub fn set(&self, hook: Arc<F>) {
+        *self.inner.lock().unwrap_or_else(PoisonError::into_inner) = Some(hook);
+    }
+
+    /// The callback is cloned out so it can be invoked without holding the lock.
+    pub fn get(&self) -> Option<Arc<F>> {
+        self.inner
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
@@ -0,0 +1,90 @@
+use crate::Event;
+
+/// Points in [`Event`] where a hook installed with [`Event::with_yield_hook`] is called, so a
+/// test harness can force specific interleavings. Hooks are called without the chain locked, so
+/// a hook may block until another thread has listened or notified on the same event.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum HookPoint {
+    /// A listener was added to the chain, before `listen` returns.
+    Listen,
+    /// A notify issued its fence, before it looks at the listeners.
+    Notify,
+    /// A notify is about to pop a waker to claim it, before it locks the chain.
+    Claim,
+}
+
+impl Event {
+    /// Creates an event that calls `hook` at each [`HookPoint`] on whichever thread reaches it.
+    pub fn with_yield_hook(hook: fn(HookPoint)) -> Self {
+        Self {
+            yield_hook: Some(hook),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::State;
+    use portable_atomic::{AtomicUsize, Ordering};
+
+    static STAGE: AtomicUsize = AtomicUsize::new(0);
+
+    /// Holds the first notify between its fence and reading the listener count until a listener
+    /// has registered.
+    fn listen_during_notify(point: HookPoint) {
+        if point == HookPoint::Notify && STAGE.swap(1, Ordering::SeqCst) == 0 {
+            while STAGE.load(Ordering::SeqCst) != 2 {
+                std::thread::yield_now();
+            }
+        }
+    }
+
+    static LISTENED: AtomicUsize = AtomicUsize::new(0);
+
+    /// Holds the first listen after it joined the chain until the event has been notified.
+    fn notify_during_listen(point: HookPoint) {
+        if point == HookPoint::Listen && LISTENED.swap(1, Ordering::SeqCst) == 0 {
+            while LISTENED.load(Ordering::SeqCst) != 2 {
+                std::thread::yield_now();
+            }
+        }
+    }
+
+    #[test]
+    fn hook_can_wait_for_notify() {
+        let event = &Event::with_yield_hook(notify_during_listen);
+        std::thread::scope(|s| {
+            let jh = s.spawn(move || {
+                let guard = event.listen();
+                guard.wait();
+                guard.get_state()
+            });
+            while LISTENED.load(Ordering::SeqCst) != 1 {
+                std::thread::yield_now();
+            }
+            assert!(event.notify_one());
+            LISTENED.store(2, Ordering::SeqCst);
+            assert_eq!(jh.join().expect("couldn't join!"), State::Notified);
+        });
+    }
+
+    #[test]
+    fn listen_after_notify_fence() {
+        let event = &Event::with_yield_hook(listen_during_notify);
+        std::thread::scope(|s| {
+            let jh = s.spawn(move || {
+                while STAGE.load(Ordering::SeqCst) != 1 {
+                    std::thread::yield_now();
+                }
+                let guard = event.listen();
+                STAGE.store(2, Ordering::SeqCst);
+                guard.wait();
+                guard.get_state()
+            });
+            event.notify_one();
+            assert_eq!(jh.join().expect("couldn't join!"), State::Notified);
+        });
+    }
+}
@@ -0,0 +1,122 @@
+use crate::waker::Waker;
+use crate::{Event, ListenerSnapshot, State, WaitGuard};
+use std::collections::BTreeMap;
+use std::sync::MutexGuard;
+
+impl<T> Event<T> {
+    fn keyed(&self) -> MutexGuard<'_, BTreeMap<u64, Vec<Waker>>> {
+        self.chain.get().keyed()
+    }
+
+    /// Listens for [`Event::notify_key`] with `key`, for multiplexing many conditions (e.g. one per
+    /// request) over a single event. Keyed listeners are indexed by key rather than queued in the
+    /// chain, so the other notify calls don't wake them. Closing the event does. Dropping the guard
+    /// while it's still waiting removes it from the index.
+    pub fn listen_with_key(&self, key: u64) -> WaitGuard {
+        let (mut waker, mut guard) = Waker::new();
+        guard.set_keyed(self.chain.get(), key);
+        // closed is only set with the chain locked
+        let _chain = self.chain();
+        if self.is_closed() {
+            waker.claim_as(State::Closed);
+            return guard;
+        }
+        self.keyed().entry(key).or_default().push(waker);
+        guard
+    }
+
+    /// Wakes every listener registered under `key`, returning how many were still waiting.
+    pub fn notify_key(&self, key: u64) -> usize {
+        let count = self.begin_notify();
+        let mut batch = self.keyed().remove(&key).unwrap_or_default();
+        batch.iter_mut().for_each(|node| {
+            node.claim();
+        });
+        let mut panicked = None;
+        let woken = self.wake_batch(&batch, &mut panicked);
+        self.compact(count);
+        crate::resume_panic(panicked);
+        woken
+    }
+
+    pub(crate) fn keyed_snapshot(&self) -> Vec<ListenerSnapshot> {
+        self.keyed()
+            .values()
+            .flatten()
+            .map(Waker::snapshot)
+            .collect()
+    }
+
+    /// Removes every keyed listener, claiming them in `state`. Called with the chain locked.
+    pub(crate) fn take_keyed(&self, state: State) -> Vec<Waker> {
+        let mut batch: Vec<_> = core::mem::take(&mut *self.keyed())
+            .into_values()
+            .flatten()
+            .collect();
+        batch.iter_mut().for_each(|node| {
+            node.claim_as(state);
+        });
+        batch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn wakes_only_matching_key() {
+        let event = Event::default();
+        let first = event.listen_with_key(1);
+        let also_first = event.listen_with_key(1);
+        let second = event.listen_with_key(2);
+        let plain = event.listen();
+        assert_eq!(event.notify_key(1), 2);
+        assert_eq!(first.get_state(), State::Notified);
+        assert_eq!(also_first.get_state(), State::Notified);
+        assert_eq!(second.get_state(), State::Waiting);
+        assert_eq!(event.notify_key(1), 0);
+        assert_eq!(event.notify_all(), 1);
+        assert_eq!(plain.get_state(), State::Notified);
+        assert_eq!(second.get_state(), State::Waiting);
+        event.close();
+        assert_eq!(second.get_state(), State::Closed);
+        assert_eq!(event.listen_with_key(2).get_state(), State::Closed);
+    }
+
+    #[test]
+    fn dropped_keyed_listener() {
+        let event = Event::default();
+        drop(event.listen_with_key(7));
+        assert!(event.keyed().is_empty());
+        let _other = event.listen_with_key(7);
+        drop(event.listen_with_key(7));
+        for key in 0..1000 {
+            drop(event.listen_with_key(key));
+        }
+        assert_eq!(event.keyed().len(), 1);
+        assert_eq!(event.keyed()[&7].len(), 1);
+        let guard = event.listen_with_key(7);
+        assert_eq!(event.keyed()[&7].len(), 2);
+        assert_eq!(event.notify_key(7), 2);
+        assert_eq!(guard.get_state(), State::Notified);
+        assert!(event.keyed().is_empty());
+    }
+
+    #[test]
+    fn keyed_across_threads() {
+        let event = &Event::default();
+        thread::scope(|s| {
+            let jh = s.spawn(move || {
+                let guard = event.listen_with_key(3);
+                guard.wait();
+                guard.get_state()
+            });
+            while event.notify_key(3) == 0 {
+                thread::yield_now();
+            }
+            assert_eq!(jh.join().expect("couldn't join!"), State::Notified);
+        });
+    }
+}
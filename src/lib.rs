@@ -1,122 +1,2638 @@
 #![allow(dead_code)]
 
+/// `tracing::trace!` with the `tracing` feature, nothing at all without it.
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::trace!($($arg)*);
+    };
+}
+
+/// Calls the event's yield hook at `point` with the `test-hooks` feature, nothing without it.
+macro_rules! hook_point {
+    ($event:expr, $point:ident) => {
+        #[cfg(feature = "test-hooks")]
+        if let Some(hook) = $event.yield_hook {
+            hook($crate::HookPoint::$point);
+        }
+    };
+}
+
+mod awaitable;
+mod batch;
+mod builder;
+mod chain;
+#[cfg(all(feature = "os-signal", unix))]
+mod fd;
+mod gate;
+mod handle;
+mod hierarchy;
+mod hook;
+#[cfg(feature = "test-hooks")]
+mod hooks;
+mod keyed;
+mod listener;
+mod node;
+mod pad;
+mod park;
+mod payload;
+mod prepare;
+mod set;
+mod sharded;
+mod split;
+mod timer;
 mod waker;
-use concurrent_queue::ConcurrentQueue;
+mod watch;
+
+use crate::chain::ChainCell;
+use crate::hook::Hook;
+use crate::pad::CachePadded;
+use crate::waker::{ListenerState, Waker};
+use core::any::Any;
+use portable_atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::collections::VecDeque;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, OnceLock, Weak};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+pub use batch::BatchListener;
+pub use builder::{EventBuilder, WakeOrder};
+pub use gate::Gate;
+pub use handle::EventHandle;
+#[cfg(feature = "test-hooks")]
+pub use hooks::HookPoint;
+pub use listener::{EventListener, Listener, RecvError};
+pub use node::ListenerNode;
+pub use prepare::PreparedListen;
+pub use set::EventSet;
+pub use sharded::ShardedEvent;
+pub use split::{Notifier, Subscriber};
+pub use timer::ScheduledNotify;
+pub use waker::{
+    Cancelled, Closed, CompletedGuard, Full, ListenError, ListenerSnapshot, Notified, Outcome,
+    PendingGuard, State, Unnotified, WaitError, WaitGuard, WaitStatus, Waited, WakeInfo, WakerKind,
+};
+
+/// Runs `f`, keeping its panic in `panicked` (unless there already is one) instead of unwinding.
+fn isolate_panic(panicked: &mut Option<Box<dyn Any + Send>>, f: impl FnOnce()) {
+    if let Err(payload) = std::panic::catch_unwind(AssertUnwindSafe(f)) {
+        panicked.get_or_insert(payload);
+    }
+}
+
+fn resume_panic(panicked: Option<Box<dyn Any + Send>>) {
+    if let Some(payload) = panicked {
+        std::panic::resume_unwind(payload);
+    }
+}
 
-use crate::waker::Waker;
-use portable_atomic::{AtomicUsize, Ordering};
+/// Most listener states an event keeps around for reuse.
+const MAX_POOL: usize = 32;
 
-pub use waker::{State, WaitGuard};
+/// Shortest chain `listen` bothers sweeping dropped listeners out of.
+const MIN_SWEEP: usize = 64;
 
 #[derive(Debug)]
-pub struct Event {
-    chain: ConcurrentQueue<Waker>,
-    num_listeners: AtomicUsize,
+pub struct Event<T = ()> {
+    chain: ChainCell,
+    auto_compact: usize,
+    notify_count: CachePadded<AtomicUsize>,
+    drain: Mutex<()>,
+    permit_mode: AtomicBool,
+    /// Banked notifications, each completes one future listener. Only touched with the chain
+    /// locked.
+    permits: AtomicUsize,
+    closed: AtomicBool,
+    next_ticket: AtomicU64,
+    seq: AtomicU64,
+    high_water: AtomicUsize,
+    on_high_water: Hook<dyn Fn(usize) + Send + Sync>,
+    registered: OnceLock<Box<Event>>,
+    /// Events created with [`Event::new_child`], woken by this event's [`Event::notify_all`].
+    children: Mutex<Vec<Weak<Event>>>,
+    /// States of woken listeners, reused by later listeners once their guards are gone.
+    pool: Mutex<Vec<Arc<ListenerState>>>,
+    /// Values handed over by [`Event::notify_one_with`] that the woken guard hasn't taken yet.
+    payloads: Mutex<Vec<(Arc<ListenerState>, T)>>,
+    /// The value stored with the permit by [`Event::notify_one_with`] in permit mode, handed to
+    /// the listener that consumes it. Only locked after the chain.
+    permit_value: Mutex<Option<T>>,
+    /// The value last given to [`Event::publish`] and how many values have been published.
+    latest: Mutex<(u64, Option<T>)>,
+    /// Most listeners the chain may hold, `usize::MAX` when unbounded.
+    capacity: usize,
+    /// Chain length at which `listen` next checks for dropped listeners to sweep out. Only touched
+    /// with the chain locked.
+    sweep_at: AtomicUsize,
+    lifo: AtomicBool,
+    /// Set once a listener registers with a priority, until then pops skip the priority scan.
+    prioritized: AtomicBool,
+    /// How many times a sync waiter spins before parking.
+    spins: u32,
+    #[cfg(feature = "latency")]
+    on_wake: Hook<dyn Fn(Duration) + Send + Sync>,
+    #[cfg(feature = "test-hooks")]
+    yield_hook: Option<fn(HookPoint)>,
+}
+
+/// What [`Event::notify_or_store`] did with a notification.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum NotifyResult {
+    /// This many live listeners were woken.
+    Woke(usize),
+    /// Nobody was listening, a permit was stored for the next listener.
+    Stored,
+}
+
+/// Why [`Event::notify_one_reason`] did or didn't wake someone.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum NotifyOutcome {
+    Woke,
+    /// The chain was empty.
+    NoListeners,
+    /// Every listener in the chain had been dropped.
+    AllDropped,
+}
+
+/// An [`Event`] shared between threads or tasks. All of the event's methods are available
+/// through deref so there's no need to write `(*shared).listen()`.
+pub type SharedEvent = std::sync::Arc<Event>;
+
+impl<T> AsRef<Event<T>> for Event<T> {
+    fn as_ref(&self) -> &Event<T> {
+        self
+    }
 }
 
 impl Default for Event {
     fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Event {
+    /// Creates an event, usable in a `static`.
+    ///
+    /// ```
+    /// # use wake_me::Event;
+    /// static SHUTDOWN: Event = Event::new();
+    /// SHUTDOWN.notify_all();
+    /// ```
+    pub const fn new() -> Self {
+        Self::with_payload()
+    }
+
+    pub fn new_shared() -> SharedEvent {
+        SharedEvent::default()
+    }
+
+    /// Creates an event that sweeps dropped listeners out of the chain after every `every` notify
+    /// calls. An `every` of 0 disables the sweep.
+    pub fn with_auto_compact(every: usize) -> Self {
         Self {
-            chain: ConcurrentQueue::unbounded(),
-            num_listeners: Default::default(),
+            auto_compact: every,
+            ..Default::default()
         }
     }
+
+    /// Creates an event that holds at most `capacity` listeners, see [`EventBuilder::bounded`].
+    pub fn bounded(capacity: usize) -> Self {
+        Self::builder().bounded(capacity).build()
+    }
 }
 
-impl Event {
-    pub fn listen(&self) -> WaitGuard {
-        let (waker, guard) = Waker::new();
-        self.num_listeners.fetch_add(1, Ordering::Release);
-        self.chain.push(waker).expect("couldn't push to queue");
-        guard
+impl<T> Event<T> {
+    /// Creates an event whose notifications can carry a `T`, see [`Event::notify_one_with`].
+    /// Plain events are `Event<()>`, created with [`Event::new`].
+    pub const fn with_payload() -> Self {
+        Self {
+            chain: ChainCell::new(),
+            auto_compact: 0,
+            notify_count: CachePadded::new(AtomicUsize::new(0)),
+            drain: Mutex::new(()),
+            permit_mode: AtomicBool::new(false),
+            permits: AtomicUsize::new(0),
+            closed: AtomicBool::new(false),
+            next_ticket: AtomicU64::new(0),
+            seq: AtomicU64::new(0),
+            high_water: AtomicUsize::new(0),
+            on_high_water: Hook::new(),
+            registered: OnceLock::new(),
+            children: Mutex::new(Vec::new()),
+            pool: Mutex::new(Vec::new()),
+            payloads: Mutex::new(Vec::new()),
+            permit_value: Mutex::new(None),
+            latest: Mutex::new((0, None)),
+            capacity: usize::MAX,
+            sweep_at: AtomicUsize::new(MIN_SWEEP),
+            lifo: AtomicBool::new(false),
+            prioritized: AtomicBool::new(false),
+            spins: 0,
+            #[cfg(feature = "latency")]
+            on_wake: Hook::new(),
+            #[cfg(feature = "test-hooks")]
+            yield_hook: None,
+        }
     }
 
-    pub fn listen_async(&self, waker: core::task::Waker) -> WaitGuard {
-        let (waker, guard) = Waker::new_async(waker);
-        self.num_listeners.fetch_add(1, Ordering::Release);
-        self.chain.push(waker).expect("couldn't push to queue");
+    fn chain(&self) -> MutexGuard<'_, VecDeque<Waker>> {
+        self.chain.get().lock()
+    }
+
+    /// Only updated with the chain locked, see [`Event::check_listeners`].
+    fn num_listeners(&self) -> &AtomicUsize {
+        &self.chain.get().len
+    }
+
+    /// Enables permit mode. A [`Event::notify_one`] that finds no live listener stores a single
+    /// permit and the next listener to register consumes it, completing without being notified.
+    /// This is how `tokio::sync::Notify::notify_one` behaves, [`Event::notify_or_store`] does the
+    /// same for a single call without enabling the mode.
+    pub fn enable_permit_mode(&self) {
+        self.permit_mode.store(true, Ordering::SeqCst);
+    }
+
+    /// Switches which listener [`Event::notify_one`] wakes first. [`WakeOrder::Lifo`] wakes the
+    /// most recently registered listener, whose thread is the most likely to still have a warm
+    /// cache. Listeners already in the chain are affected too.
+    pub fn set_wake_order(&self, order: WakeOrder) {
+        self.lifo.store(order == WakeOrder::Lifo, Ordering::Relaxed);
+    }
+
+    pub fn wake_order(&self) -> WakeOrder {
+        if self.lifo.load(Ordering::Relaxed) {
+            WakeOrder::Lifo
+        } else {
+            WakeOrder::Fifo
+        }
+    }
+
+    /// Notifications that were requested but not yet consumed by a listener, stored by permit mode
+    /// (at most one) or banked by [`Event::notify_additional`].
+    pub fn pending_notifications(&self) -> usize {
+        let _chain = self.chain();
+        self.permits.load(Ordering::Relaxed)
+    }
+
+    /// Discards every stored notification (see [`Event::pending_notifications`]) so the event can
+    /// be reused from a clean slate, e.g. between test iterations or when recycled from a pool.
+    /// With `sweep_dropped` dropped listeners are swept out of the chain at the same time.
+    /// Registered listeners stay registered. Returns how many notifications were discarded.
+    pub fn reset(&self, sweep_dropped: bool) -> usize {
+        let mut chain = self.chain();
+        if sweep_dropped {
+            self.remove_dropped(&mut chain);
+        }
+        self.permit_value().take();
+        self.permits.swap(0, Ordering::Relaxed)
+    }
+
+    /// Counts the notify calls made on the event so far, whether or not they woke anyone. Compare
+    /// it later with [`Event::is_notified_since`] to find out if something happened in between
+    /// without registering a listener. Notifying a closed event doesn't advance it.
+    ///
+    /// ```
+    /// # use wake_me::Event;
+    /// let event = Event::default();
+    /// let epoch = event.epoch();
+    /// assert!(!event.is_notified_since(epoch));
+    /// event.notify_one();
+    /// assert!(event.is_notified_since(epoch));
+    /// ```
+    pub fn epoch(&self) -> usize {
+        self.notify_count()
+    }
+
+    /// Whether the event was notified or closed after `epoch` was read from [`Event::epoch`].
+    pub fn is_notified_since(&self, epoch: usize) -> bool {
+        // the count wraps, compare for inequality rather than order
+        self.notify_count() != epoch || self.is_closed()
+    }
+
+    /// The listener count is only updated with the chain locked so the two never legitimately
+    /// differ, drift here means a notify may be skipped while a listener is still registered.
+    fn check_listeners(&self, chain: &VecDeque<Waker>) {
+        debug_assert_eq!(
+            self.num_listeners().load(Ordering::Relaxed),
+            chain.len(),
+            "listener count diverged from the chain"
+        );
+    }
+
+    /// The most listeners the event holds, `None` if it is unbounded.
+    pub fn capacity(&self) -> Option<usize> {
+        (self.capacity != usize::MAX).then_some(self.capacity)
+    }
+
+    /// Adds the waker to the back of the chain and returns its ticket. Tickets are handed out with
+    /// the chain locked so the chain is always in ticket order.
+    fn push(&self, mut waker: Waker) -> u64 {
+        let mut chain = self.chain();
+        self.check_listeners(&chain);
+        // only ever written with the chain locked, no need for an atomic increment
+        let ticket = self.next_ticket.load(Ordering::Relaxed);
+        self.next_ticket.store(ticket + 1, Ordering::Relaxed);
+        // closed is only set with the chain locked, nothing can slip in after close drained it
+        if self.closed.load(Ordering::Relaxed) {
+            waker.claim_as(State::Closed);
+            return ticket;
+        }
+        // as is the sequence, a listener that is already behind has nothing to wait for
+        if waker
+            .seq()
+            .is_some_and(|seq| seq < self.seq.load(Ordering::Relaxed))
+        {
+            waker.complete();
+            return ticket;
+        }
+        // permits are only touched with the chain locked so they can't race a notify
+        if self.permits.load(Ordering::Relaxed) != 0 {
+            self.permits.fetch_sub(1, Ordering::Relaxed);
+            let value = self.permit_value().take();
+            let state = waker.shared_state();
+            waker.complete();
+            drop(chain);
+            // the guard isn't handed out until listen returns, it can't look for the value sooner
+            if let (Some(state), Some(value)) = (state, value) {
+                self.payloads().push((state, value));
+            }
+            return ticket;
+        }
+        if chain.len() >= self.sweep_at.load(Ordering::Relaxed) {
+            self.sweep(&mut chain);
+        }
+        // dropped listeners don't count against the capacity, they are only swept when it matters
+        if chain.len() >= self.capacity && self.remove_dropped(&mut chain) == 0 {
+            waker.claim_as(State::Full);
+            return ticket;
+        }
+        // SeqCst pairs with the fence in notify so either the notifier sees this listener or the
+        // listener sees the data published before the notify
+        let count = self.num_listeners().fetch_add(1, Ordering::SeqCst) + 1;
+        trace!(id = waker.key(), listeners = count, "listen");
+        chain.push_back(waker);
+        drop(chain);
+        hook_point!(self, Listen);
+        if let Some(registered) = self.registered.get() {
+            registered.notify_all();
+        }
+        if count == self.high_water.load(Ordering::Relaxed) {
+            if let Some(on_high_water) = self.on_high_water.get() {
+                on_high_water(count);
+            }
+        }
+        ticket
+    }
+
+    /// Removes the dropped listeners from the locked `chain` once they make up half of it, then
+    /// schedules the next check for when the chain has doubled so listen stays amortized O(1).
+    fn sweep(&self, chain: &mut VecDeque<Waker>) {
+        let dropped = chain
+            .iter()
+            .filter(|node| node.state() == State::Dropped)
+            .count();
+        if dropped * 2 >= chain.len() {
+            self.remove_dropped(chain);
+        }
+        self.sweep_at
+            .store((chain.len() * 2).max(MIN_SWEEP), Ordering::Relaxed);
+    }
+
+    /// Pushes the waker and records its ticket on `guard`.
+    fn register(&self, waker: Waker, mut guard: WaitGuard) -> WaitGuard {
+        guard.set_spins(self.spins);
+        guard.set_chain(self.chain.get());
+        guard.set_ticket(self.push(waker));
         guard
     }
 
-    pub fn notify_one(&self) {
-        portable_atomic::fence(Ordering::SeqCst);
-        if self.num_listeners.load(Ordering::Relaxed) == 0 {
-            return;
+    /// Notified on every `listen` once someone has waited for listeners.
+    fn registered(&self) -> &Event {
+        self.registered.get_or_init(Box::default)
+    }
+
+    /// Blocks until at least `n` listeners are registered, so a producer doesn't notify before its
+    /// consumers are listening. Dropped listeners that are still in the chain are counted. Returns
+    /// early if the event is closed, nobody can register after that.
+    pub fn wait_for_listeners(&self, n: usize) {
+        self.wait_for_listeners_until(n, None);
+    }
+
+    /// Returns false if the deadline passed or the event was closed before `n` listeners registered.
+    fn wait_for_listeners_until(&self, n: usize, deadline: Option<Instant>) -> bool {
+        let registered = self.registered();
+        loop {
+            // listen before checking so a registration in between wakes us
+            let guard = registered.listen();
+            if self.num_listeners().load(Ordering::SeqCst) >= n {
+                return true;
+            }
+            if guard.wait_opt_deadline(deadline).is_err() {
+                return self.num_listeners().load(Ordering::SeqCst) >= n;
+            }
+        }
+    }
+
+    /// Notifies one listener, waiting up to `timeout` for one to register if nobody is listening.
+    /// Returns whether a listener was woken, or in permit mode (see [`Event::enable_permit_mode`])
+    /// whether the notification was stored for the next one. A closed event returns false at once.
+    pub fn notify_one_or_timeout(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if self.is_closed() {
+                return false;
+            }
+            if self.notify_one_detailed().is_some() {
+                return true;
+            }
+            // the stored permit is taken by the next listen, which never joins the chain
+            if self.permit_mode.load(Ordering::Relaxed) && !self.is_closed() {
+                return true;
+            }
+            if !self.wait_for_listeners_until(1, Some(deadline)) {
+                return false;
+            }
+        }
+    }
+
+    /// Blocks while `condition` returns true, like [`std::sync::Condvar::wait_while`], with the
+    /// notifier expected to notify after changing whatever `condition` looks at. The condition is
+    /// always checked again after listening, so a change and notify in between isn't missed.
+    /// Fails with [`WaitError::Closed`] if the event was closed with the condition still holding,
+    /// or with [`WaitError::Full`] if the event is bounded and has no room to listen.
+    ///
+    /// ```
+    /// # use wake_me::Event;
+    /// # use std::sync::atomic::{AtomicUsize, Ordering};
+    /// let event = Event::default();
+    /// let queued = AtomicUsize::new(0);
+    /// std::thread::scope(|s| {
+    ///     s.spawn(|| {
+    ///         queued.store(1, Ordering::SeqCst);
+    ///         event.notify_all();
+    ///     });
+    ///     event.wait_while(|| queued.load(Ordering::SeqCst) == 0).unwrap();
+    /// });
+    /// ```
+    pub fn wait_while(&self, condition: impl FnMut() -> bool) -> Result<(), WaitError> {
+        self.wait_while_opt_deadline(None, condition)
+    }
+
+    /// [`Event::wait_while`] giving up with [`WaitError::Timeout`] once `deadline` passes with the
+    /// condition still holding, like [`std::sync::Condvar::wait_timeout_while`].
+    pub fn wait_timeout_while(
+        &self,
+        deadline: Instant,
+        condition: impl FnMut() -> bool,
+    ) -> Result<(), WaitError> {
+        self.wait_while_opt_deadline(Some(deadline), condition)
+    }
+
+    /// Blocks until `condition` returns true, [`Event::wait_while`] with the condition negated.
+    ///
+    /// ```
+    /// # use wake_me::Event;
+    /// # use std::sync::atomic::{AtomicBool, Ordering};
+    /// let event = Event::default();
+    /// let ready = AtomicBool::new(false);
+    /// std::thread::scope(|s| {
+    ///     s.spawn(|| {
+    ///         ready.store(true, Ordering::SeqCst);
+    ///         event.notify_all();
+    ///     });
+    ///     event.wait_until(|| ready.load(Ordering::SeqCst)).unwrap();
+    /// });
+    /// ```
+    pub fn wait_until(&self, mut condition: impl FnMut() -> bool) -> Result<(), WaitError> {
+        self.wait_while_opt_deadline(None, || !condition())
+    }
+
+    fn wait_while_opt_deadline(
+        &self,
+        deadline: Option<Instant>,
+        mut condition: impl FnMut() -> bool,
+    ) -> Result<(), WaitError> {
+        while condition() {
+            let guard = self.listen();
+            // checked again after listening, a change and notify in between wouldn't wake us
+            if !condition() {
+                return Ok(());
+            }
+            if let Err(err) = guard.wait_opt_deadline(deadline) {
+                return if condition() { Err(err) } else { Ok(()) };
+            }
         }
-        while let Ok(node) = self.chain.pop() {
-            self.num_listeners.fetch_sub(1, Ordering::Release);
-            if node.wake() {
-                return;
+        Ok(())
+    }
+
+    /// Async version of [`Event::wait_until`].
+    pub async fn wait_until_async(
+        &self,
+        mut condition: impl FnMut() -> bool,
+    ) -> Result<(), WaitError> {
+        while !condition() {
+            let guard = core::future::poll_fn(|cx| {
+                core::task::Poll::Ready(self.listen_async(cx.waker().clone()))
+            })
+            .await;
+            if condition() {
+                return Ok(());
+            }
+            core::future::poll_fn(|cx| guard.register_and_poll(cx)).await;
+            if let Err(err) = guard.state_to_err() {
+                return if condition() { Ok(()) } else { Err(err) };
             }
         }
+        Ok(())
     }
 
-    // Can we add a take function to the queue to optimise this? / Would that actually be better?
-    pub fn notify_all(&self) {
-        portable_atomic::fence(Ordering::SeqCst);
-        let len = self.num_listeners.load(Ordering::Relaxed);
-        for _ in 0..len {
-            if let Ok(node) = self.chain.pop() {
-                self.num_listeners.fetch_sub(1, Ordering::Release);
-                node.wake();
+    /// Async version of [`Event::wait_for_listeners`].
+    pub async fn wait_for_listeners_async(&self, n: usize) {
+        let registered = self.registered();
+        let mut guard = None;
+        core::future::poll_fn(|cx| {
+            guard = Some(registered.listen_async(cx.waker().clone()));
+            if self.num_listeners().load(Ordering::SeqCst) >= n || self.is_closed() {
+                core::task::Poll::Ready(())
             } else {
-                return;
+                core::task::Poll::Pending
             }
+        })
+        .await
+    }
+
+    /// Registers a callback invoked with the listener count whenever a `listen` brings the number
+    /// of registered listeners up to `threshold`, so producers can throttle when consumers fall
+    /// behind. It fires once per upward crossing.
+    pub fn set_high_water(&self, threshold: usize, f: impl Fn(usize) + Send + Sync + 'static) {
+        self.on_high_water.set(Arc::new(f));
+        self.high_water.store(threshold, Ordering::Relaxed);
+    }
+
+    /// Pops the front waker, claiming it before the chain is unlocked. Wakers are only ever
+    /// claimed with the chain locked which is what lets a pinned node unlink itself safely.
+    fn pop(&self) -> Option<Waker> {
+        self.pop_with(Ordering::SeqCst)
+    }
+
+    /// [`Event::pop`] claiming with the given ordering.
+    fn pop_with(&self, success: Ordering) -> Option<Waker> {
+        self.pop_from(self.wake_order(), success)
+    }
+
+    /// [`Event::pop_with`] taking from the end of the chain picked by `order` rather than the
+    /// event's wake order.
+    fn pop_from(&self, order: WakeOrder, success: Ordering) -> Option<Waker> {
+        hook_point!(self, Claim);
+        let mut chain = self.chain();
+        self.check_listeners(&chain);
+        let index = match order {
+            _ if self.prioritized.load(Ordering::Relaxed) => Self::priority_index(&chain, order)?,
+            WakeOrder::Fifo => 0,
+            WakeOrder::Lifo => chain.len().checked_sub(1)?,
+        };
+        let mut node = chain.remove(index)?;
+        self.num_listeners().fetch_sub(1, Ordering::Release);
+        node.claim_with(State::Notified, success);
+        Some(node)
+    }
+
+    /// The live listener with the highest priority, the one picked by `order` among equals. Falls
+    /// back to the end picked by `order` when every listener has been dropped.
+    fn priority_index(chain: &VecDeque<Waker>, order: WakeOrder) -> Option<usize> {
+        let live = chain
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.state() != State::Dropped);
+        // max_by_key keeps the last of equal maximums
+        let best = match order {
+            WakeOrder::Fifo => live.rev().max_by_key(|(_, node)| node.priority()),
+            WakeOrder::Lifo => live.max_by_key(|(_, node)| node.priority()),
+        };
+        match (best, order) {
+            (Some((index, _)), _) => Some(index),
+            (None, WakeOrder::Fifo) => (!chain.is_empty()).then_some(0),
+            (None, WakeOrder::Lifo) => chain.len().checked_sub(1),
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::waker::State;
-    use portable_atomic::{AtomicUsize, Ordering};
-    use std::thread;
-    use std::time::Duration;
+    /// Takes every waker out of the chain, claiming them before the chain is unlocked.
+    fn take_all(&self) -> VecDeque<Waker> {
+        self.take_all_with(Ordering::SeqCst)
+    }
 
-    #[test]
-    fn it_works() {
-        let event = Event::default();
-        let value = AtomicUsize::new(0);
+    /// [`Event::take_all`] claiming with the given ordering. The listener count is reset rather
+    /// than decremented, recovering a count that has drifted (which [`Event::check_listeners`]
+    /// reports in debug builds).
+    fn take_all_with(&self, success: Ordering) -> VecDeque<Waker> {
+        let mut chain = self.chain();
+        chain.iter_mut().for_each(|node| {
+            node.claim_with(State::Notified, success);
+        });
+        let batch = core::mem::take(&mut *chain);
+        self.num_listeners().store(0, Ordering::Release);
+        batch
+    }
 
-        thread::scope(|s| {
-            let jh = s.spawn(|| {
-                let guard = event.listen();
-                assert_eq!(value.load(Ordering::Acquire), 0);
-                assert_eq!(guard.get_state(), State::Waiting);
-                guard.wait();
-                assert_eq!(value.load(Ordering::Acquire), 42);
-                assert_eq!(guard.get_state(), State::Notified);
+    /// Removes and claims the first waker in the chain matching `f`.
+    fn take_first(&self, mut f: impl FnMut(&Waker) -> bool) -> Option<Waker> {
+        let mut chain = self.chain();
+        self.check_listeners(&chain);
+        let index = chain.iter().position(&mut f)?;
+        let mut node = chain.remove(index)?;
+        self.num_listeners().fetch_sub(1, Ordering::Release);
+        node.claim();
+        Some(node)
+    }
+
+    /// Registers the current thread. Once the event is closed the guard is returned already in
+    /// [`State::Closed`], see [`Event::try_listen`].
+    pub fn listen(&self) -> WaitGuard {
+        let (waker, guard) = Waker::new_reusing(self.pooled_state());
+        self.register(waker, guard)
+    }
+
+    /// Like [`Event::listen`] but the guard only gives up its outcome once it has been waited on.
+    pub fn listen_typed(&self) -> PendingGuard {
+        PendingGuard::new(self.listen())
+    }
+
+    /// Like [`Event::listen`] but fails if the event has been closed, or if it is bounded (see
+    /// [`EventBuilder::bounded`]) and already full. [`Event::listen`] on a full event returns a
+    /// guard that is already [`State::Full`].
+    pub fn try_listen(&self) -> Result<WaitGuard, ListenError> {
+        let guard = self.listen();
+        match guard.get_state() {
+            State::Closed => Err(ListenError::Closed),
+            State::Full => Err(ListenError::Full),
+            _ => Ok(guard),
+        }
+    }
+
+    /// Shuts the event down for good. Every registered listener is woken in [`State::Closed`],
+    /// later listeners complete as closed straight away and notifies do nothing.
+    pub fn close(&self) {
+        let batch = {
+            let mut chain = self.chain();
+            self.check_listeners(&chain);
+            self.closed.store(true, Ordering::Relaxed);
+            self.permits.store(0, Ordering::Relaxed);
+            self.permit_value().take();
+            chain.iter_mut().for_each(|node| {
+                node.claim_as(State::Closed);
             });
-            thread::sleep(Duration::from_millis(50));
-            value.store(42, Ordering::Release);
-            event.notify_one();
+            let mut batch = core::mem::take(&mut *chain);
+            self.num_listeners()
+                .fetch_sub(batch.len(), Ordering::Release);
+            batch.extend(self.take_keyed(State::Closed));
+            batch
+        };
+        // whoever waits for listeners would wait forever, nobody can register any more
+        if let Some(registered) = self.registered.get() {
+            registered.close();
+        }
+        let mut panicked = None;
+        for node in &batch {
+            if node.is_claimed() {
+                isolate_panic(&mut panicked, || node.unpark());
+            }
+        }
+        resume_panic(panicked);
+    }
 
-            jh.join().expect("couldn't join!");
-        })
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Relaxed)
     }
-    #[test]
-    fn drop_count() {
-        let event = Event::default();
-        let value = AtomicUsize::new(0);
 
-        thread::scope(|s| {
-            let jh = s.spawn(|| {
-                {
-                    let _guard_a = event.listen();
-                    let _guard_b = event.listen();
+    /// Number of registrations in the chain, including dropped listeners that haven't been swept
+    /// out yet.
+    pub fn listener_count(&self) -> usize {
+        self.num_listeners().load(Ordering::Relaxed)
+    }
+
+    pub fn has_listeners(&self) -> bool {
+        self.listener_count() != 0
+    }
+
+    /// Listeners that were dropped but are still in the chain, until a notify skips over them or
+    /// [`Event::clear_dropped`] sweeps them out.
+    pub fn stale_listeners(&self) -> usize {
+        self.chain()
+            .iter()
+            .filter(|node| node.state() == State::Dropped)
+            .count()
+    }
+
+    /// Panics unless every registration counted by [`Event::listener_count`] is still waiting,
+    /// i.e. no dropped listeners are lingering in the chain. Meant for tests and debug builds
+    /// checking that listen / drop bookkeeping is balanced.
+    #[cfg(debug_assertions)]
+    pub fn assert_balanced(&self) {
+        let chain = self.chain();
+        let waiting = chain
+            .iter()
+            .filter(|node| node.state() == State::Waiting)
+            .count();
+        let count = self.num_listeners().load(Ordering::Relaxed);
+        assert_eq!(
+            count, waiting,
+            "{count} listeners counted but only {waiting} are waiting"
+        );
+    }
+
+    pub fn listen_async(&self, waker: core::task::Waker) -> WaitGuard {
+        let (waker, guard) = Waker::new_async_reusing(self.pooled_state(), waker);
+        self.register(waker, guard)
+    }
+
+    /// Re-arms a guard that has been notified (see [`WaitGuard::try_rearm`]) and registers it
+    /// again for the current thread. Returns false, leaving the guard alone, if it wasn't notified.
+    pub fn rearm(&self, guard: &WaitGuard) -> bool {
+        if !guard.try_rearm() {
+            return false;
+        }
+        guard.set_ticket(self.push(Waker::for_guard(guard)));
+        true
+    }
+
+    /// Listens with an opaque locality hint (e.g. a NUMA node) that [`Event::notify_one_local`]
+    /// uses to prefer nearby listeners.
+    pub fn listen_with_affinity(&self, node: u16) -> WaitGuard {
+        let (mut waker, guard) = Waker::new();
+        waker.set_affinity(node);
+        self.register(waker, guard)
+    }
+
+    /// Listens with a caller chosen `tag` (e.g. reader or writer) so a single event can host
+    /// different kinds of waiters, see [`Event::notify_filtered`].
+    pub fn listen_tagged(&self, tag: u64) -> WaitGuard {
+        let (mut waker, guard) = Waker::new();
+        waker.set_tag(tag);
+        self.register(waker, guard)
+    }
+
+    /// Listens with a `priority`, [`Event::notify_one`] wakes the live listener with the highest
+    /// priority first and listeners with equal priorities in the event's wake order. Plain
+    /// listeners have priority 0.
+    ///
+    /// ```
+    /// # use wake_me::{Event, State};
+    /// let event = Event::default();
+    /// let background = event.listen();
+    /// let realtime = event.listen_with_priority(10);
+    /// event.notify_one();
+    /// assert_eq!(realtime.get_state(), State::Notified);
+    /// assert_eq!(background.get_state(), State::Waiting);
+    /// ```
+    pub fn listen_with_priority(&self, priority: u8) -> WaitGuard {
+        let (mut waker, guard) = Waker::new();
+        waker.set_priority(priority);
+        self.prioritized.store(true, Ordering::Relaxed);
+        self.register(waker, guard)
+    }
+
+    /// Wakes every listener from [`Event::listen_tagged`] whose tag matches `f`, leaving the rest
+    /// registered. Untagged listeners are never woken. Returns how many live listeners were woken.
+    /// The chain is locked while `f` runs so it must not use this event.
+    pub fn notify_filtered(&self, mut f: impl FnMut(u64) -> bool) -> usize {
+        let count = self.begin_notify();
+        let batch = self.take_where(&mut self.chain(), |node| node.tag().is_some_and(&mut f));
+        let mut panicked = None;
+        let woken = self.wake_batch(&batch, &mut panicked);
+        self.compact(count);
+        resume_panic(panicked);
+        woken
+    }
+
+    /// Wakes the listener that has been waiting the longest. Listeners that re-listen after being
+    /// woken go to the back of the chain so repeated notifies rotate through all of them. With
+    /// [`WakeOrder::Lifo`] the newest listener is woken instead.
+    ///
+    /// Returns whether a live listener was woken, see [`Event::notify_one_reason`] for why not.
+    pub fn notify_one(&self) -> bool {
+        self.notify_one_detailed().is_some()
+    }
+
+    /// Wakes the most recently registered live listener whatever the event's wake order, for
+    /// schedulers that want the sleeper with the warmest cache. Returns whether one was woken.
+    pub fn notify_last(&self) -> bool {
+        let count = self.begin_notify();
+        trace!(
+            listeners = self.num_listeners().load(Ordering::Relaxed),
+            "notify_last"
+        );
+        let mut woken = false;
+        while let Some(node) = self.pop_from(WakeOrder::Lifo, Ordering::SeqCst) {
+            if self.wake(&node) {
+                woken = true;
+                break;
+            }
+        }
+        self.compact(count);
+        woken
+    }
+
+    /// Wakes up to `n` live listeners and banks whatever is left over for listeners that register
+    /// later, each consuming one. Unlike permit mode the bank accumulates, which is what a
+    /// semaphore or channel releasing several permits at once needs. Returns how many listeners
+    /// were woken now.
+    pub fn notify_additional(&self, n: usize) -> usize {
+        let count = self.begin_notify();
+        let mut woken = 0;
+        while woken < n {
+            if self.wake_next().is_some() {
+                woken += 1;
+                continue;
+            }
+            let chain = self.chain();
+            if chain.is_empty() {
+                if !self.is_closed() {
+                    self.permits.fetch_add(n - woken, Ordering::Relaxed);
                 }
-                let guard_c = event.listen();
-                guard_c.wait();
-                assert_eq!(value.load(Ordering::Acquire), 42);
-            });
-            thread::sleep(Duration::from_millis(50));
-            value.store(42, Ordering::Release);
-            assert_eq!(event.chain.len(), 3);
-            event.notify_one();
-            assert_eq!(event.chain.len(), 0);
+                break;
+            }
+            // a listener registered after the chain was drained, it gets the next notification
+        }
+        self.compact(count);
+        woken
+    }
 
-            jh.join().expect("couldn't join!");
-        })
+    /// Wakes up to `n` live listeners in arrival order, skipping dropped ones, and returns how many
+    /// were woken.
+    pub fn notify(&self, n: usize) -> usize {
+        let count = self.begin_notify();
+        let mut woken = 0;
+        while woken < n {
+            let Some(node) = self.pop() else {
+                break;
+            };
+            if self.wake(&node) {
+                woken += 1;
+            }
+        }
+        self.compact(count);
+        woken
+    }
+
+    /// [`Event::notify_one`] returning which listener was woken, if any.
+    pub fn notify_one_detailed(&self) -> Option<WakeInfo> {
+        let count = self.begin_notify();
+        trace!(
+            listeners = self.num_listeners().load(Ordering::Relaxed),
+            "notify_one"
+        );
+        let info = if self.permit_mode.load(Ordering::Relaxed) {
+            self.wake_or_store()
+        } else if self.num_listeners().load(Ordering::Relaxed) != 0 {
+            self.wake_next()
+        } else {
+            None
+        };
+        self.compact(count);
+        info
+    }
+
+    /// [`Event::notify_one`] with caller chosen orderings, for callers that establish
+    /// happens-before between their data and the listener some other way. `success` is the
+    /// ordering used to mark the listener notified and `fence` the fence issued before the chain is
+    /// read, `None` skipping it (as does `Some(Relaxed)`). [`Event::notify_one`] is
+    /// `(SeqCst, Some(SeqCst))`.
+    ///
+    /// Weakening either is only sound if the caller guarantees it. Without the fence a listener
+    /// that registered just before this call may not be seen, and the notification is lost. A
+    /// success ordering weaker than `Release` means the listener may wake without observing
+    /// anything written before the notify. Permit mode always uses the default orderings.
+    ///
+    /// Returns whether a live listener was woken, like [`Event::notify_one`].
+    pub fn notify_one_with_ordering(&self, success: Ordering, fence: Option<Ordering>) -> bool {
+        self.notify_one_ordered(success, fence)
+    }
+
+    /// [`Event::notify_one`] without the `SeqCst` fence, for hot paths that already publish their
+    /// data with their own Acquire/Release protocol. The woken listener still observes everything
+    /// written before the call.
+    ///
+    /// The fence is what guarantees a listener that registered just before the call is seen. The
+    /// caller has to guarantee that instead, by issuing a `SeqCst` fence of its own between
+    /// publishing the condition and notifying or by only notifying listeners it knows are
+    /// registered (see [`Event::wait_for_listeners`]). Otherwise the notification can be lost.
+    pub fn notify_one_relaxed(&self) -> bool {
+        self.notify_one_ordered(Ordering::Release, None)
+    }
+
+    fn notify_one_ordered(&self, success: Ordering, fence: Option<Ordering>) -> bool {
+        if self.permit_mode.load(Ordering::Relaxed) {
+            return self.notify_one();
+        }
+        let count = self.begin_notify_with(fence);
+        let mut woken = false;
+        if self.num_listeners().load(Ordering::Relaxed) != 0 {
+            while let Some(node) = self.pop_with(success) {
+                if self.wake(&node) {
+                    woken = true;
+                    break;
+                }
+            }
+        }
+        self.compact(count);
+        woken
+    }
+
+    /// [`Event::notify_one`] reporting why nobody was woken, if nobody was.
+    pub fn notify_one_reason(&self) -> NotifyOutcome {
+        let count = self.begin_notify();
+        let mut popped = false;
+        let mut woke = false;
+        while let Some(node) = self.pop() {
+            popped = true;
+            if self.wake(&node) {
+                woke = true;
+                break;
+            }
+        }
+        if !woke && self.permit_mode.load(Ordering::Relaxed) {
+            woke = self.wake_or_store().is_some();
+        }
+        self.compact(count);
+        match (woke, popped) {
+            (true, _) => NotifyOutcome::Woke,
+            (false, true) => NotifyOutcome::AllDropped,
+            (false, false) => NotifyOutcome::NoListeners,
+        }
+    }
+
+    /// Like [`Event::notify_one`] in permit mode, whether or not it is enabled, reporting whether
+    /// the notification reached a listener or was stored for the next one.
+    pub fn notify_or_store(&self) -> NotifyResult {
+        let count = self.begin_notify();
+        let result = if self.wake_or_store().is_some() {
+            NotifyResult::Woke(1)
+        } else {
+            NotifyResult::Stored
+        };
+        self.compact(count);
+        result
+    }
+
+    /// Wakes the oldest live listener registered with affinity `node`, falling back to
+    /// [`Event::notify_one`] behaviour if there is none.
+    pub fn notify_one_local(&self, node: u16) {
+        let count = self.begin_notify();
+        if self.num_listeners().load(Ordering::Relaxed) != 0 {
+            let local =
+                |waker: &Waker| waker.affinity() == Some(node) && waker.state() == State::Waiting;
+            let mut woken = false;
+            while let Some(waker) = self.take_first(local) {
+                if self.wake(&waker) {
+                    woken = true;
+                    break;
+                }
+            }
+            if !woken {
+                self.wake_next();
+            }
+        }
+        self.compact(count);
+    }
+
+    /// Wakes every listener registered so far, returning how many of them were still waiting.
+    /// Listeners on child events (see [`Event::new_child`]) are woken and counted too.
+    ///
+    /// The whole chain is taken in one go and woken after the lock is released, so concurrent
+    /// `listen` calls only wait for the swap and anyone registering afterwards stays registered.
+    pub fn notify_all(&self) -> usize {
+        self.notify_all_ordered(Ordering::SeqCst, Some(Ordering::SeqCst))
+    }
+
+    /// [`Event::notify_all`] without the `SeqCst` fence, with the same requirements on the caller
+    /// as [`Event::notify_one_relaxed`].
+    pub fn notify_all_relaxed(&self) -> usize {
+        self.notify_all_ordered(Ordering::Release, None)
+    }
+
+    fn notify_all_ordered(&self, success: Ordering, fence: Option<Ordering>) -> usize {
+        let count = self.begin_notify_with(fence);
+        let len = self.num_listeners().load(Ordering::Relaxed);
+        trace!(listeners = len, "notify_all");
+        let mut woken = 0;
+        let mut panicked = None;
+        if len != 0 {
+            let batch = self.take_all_with(success);
+            woken = self.wake_batch(&batch, &mut panicked);
+        }
+        self.compact(count);
+        woken += self.notify_children();
+        resume_panic(panicked);
+        woken
+    }
+
+    /// Wakes every listener except the one identified by `id` (see [`WaitGuard::id`]), which stays
+    /// registered. Returns how many live listeners were woken. `id` must come from a guard that is
+    /// still alive, a stale id may belong to a newer listener which is then skipped.
+    pub fn notify_all_except(&self, id: usize) -> usize {
+        let count = self.begin_notify();
+        let batch = self.take_where(&mut self.chain(), |node| node.key() != id);
+        let mut panicked = None;
+        let woken = self.wake_batch(&batch, &mut panicked);
+        self.compact(count);
+        resume_panic(panicked);
+        woken
+    }
+
+    /// Listens for the event's sequence (see [`Event::notify_seq`]) to move past `seq`, the
+    /// sequence the caller has already seen. If it already has the guard is returned notified.
+    pub fn listen_at(&self, seq: u64) -> WaitGuard {
+        let (mut waker, guard) = Waker::new();
+        waker.set_seq(seq);
+        self.register(waker, guard)
+    }
+
+    /// Advances the event's sequence to `seq` (it never goes backwards) and wakes every listener
+    /// from [`Event::listen_at`] that is now behind. Plain listeners are woken if the sequence
+    /// moved. Returns how many live listeners were woken.
+    pub fn notify_seq(&self, seq: u64) -> usize {
+        let count = self.begin_notify();
+        let batch = {
+            let mut chain = self.chain();
+            let previous = self.seq.fetch_max(seq, Ordering::Relaxed);
+            let current = previous.max(seq);
+            self.take_where(&mut chain, |node| match node.seq() {
+                Some(seen) => seen < current,
+                None => current > previous,
+            })
+        };
+        let mut panicked = None;
+        let woken = self.wake_batch(&batch, &mut panicked);
+        self.compact(count);
+        resume_panic(panicked);
+        woken
+    }
+
+    /// Removes and claims every waker in the locked `chain` matching `f`, keeping the order.
+    fn take_where(
+        &self,
+        chain: &mut VecDeque<Waker>,
+        f: impl FnMut(&Waker) -> bool,
+    ) -> VecDeque<Waker> {
+        self.check_listeners(chain);
+        let (mut batch, keep): (VecDeque<_>, VecDeque<_>) = chain.drain(..).partition(f);
+        *chain = keep;
+        batch.iter_mut().for_each(|node| {
+            node.claim();
+        });
+        self.num_listeners()
+            .fetch_sub(batch.len(), Ordering::Release);
+        batch
+    }
+
+    /// Wakes exactly the listeners registered before the call by swapping out the whole chain at
+    /// once, anyone registering afterwards stays registered. Returns how many were still waiting.
+    /// Unlike [`Event::notify_all`] child events aren't woken.
+    pub fn notify_all_snapshot(&self) -> usize {
+        let count = self.begin_notify();
+        let batch = self.take_all();
+        let mut panicked = None;
+        let woken = self.wake_batch(&batch, &mut panicked);
+        self.compact(count);
+        resume_panic(panicked);
+        woken
+    }
+
+    /// Like [`Event::notify_all`] but wakes the listeners in arrival order across concurrent calls.
+    ///
+    /// Each call takes every listener registered so far and wakes them while holding a drain lock,
+    /// so strict notifiers are serialized with each other.
+    pub fn notify_all_strict(&self) {
+        let count = self.begin_notify();
+        let _drain = self.drain.lock().unwrap_or_else(PoisonError::into_inner);
+        let batch = self.take_all();
+        let mut panicked = None;
+        self.wake_batch(&batch, &mut panicked);
+        self.compact(count);
+        resume_panic(panicked);
+    }
+
+    /// Wakes every listener then blocks until each of them has acknowledged by returning from a
+    /// wait or dropping its guard, returning how many were woken.
+    ///
+    /// Fails with [`WaitError::AckTimeout`] if they haven't all acknowledged within `timeout`.
+    /// Pinned [`ListenerNode`]s are woken but not waited for.
+    pub fn notify_all_and_wait(&self, timeout: Duration) -> Result<usize, WaitError> {
+        let deadline = Instant::now() + timeout;
+        let count = self.begin_notify();
+        let batch = self.take_all();
+        let pending: Vec<_> = batch
+            .iter()
+            .filter(|node| node.is_claimed())
+            .filter_map(Waker::shared_state)
+            .collect();
+        let mut panicked = None;
+        self.wake_batch(&batch, &mut panicked);
+        self.compact(count);
+        resume_panic(panicked);
+
+        let mut backoff = Duration::from_micros(50);
+        loop {
+            let acked = pending
+                .iter()
+                .filter(|state| waker::is_acknowledged(state))
+                .count();
+            if acked == pending.len() {
+                return Ok(acked);
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(WaitError::AckTimeout { acked });
+            }
+            std::thread::sleep(backoff.min(remaining));
+            backoff = (backoff * 2).min(Duration::from_millis(5));
+        }
+    }
+
+    /// Removes listeners whose guard has been dropped from the chain, returning how many were removed.
+    pub fn clear_dropped(&self) -> usize {
+        self.remove_dropped(&mut self.chain())
+    }
+
+    fn remove_dropped(&self, chain: &mut VecDeque<Waker>) -> usize {
+        let before = chain.len();
+        chain.retain(|node| node.state() != State::Dropped);
+        let removed = before - chain.len();
+        self.num_listeners().fetch_sub(removed, Ordering::Release);
+        removed
+    }
+
+    /// Calls `f` with the current state of every listener in the chain without removing or
+    /// waking any of them. The chain stays locked while visiting so `f` must not use this event.
+    pub fn for_each_listener<F: FnMut(&State)>(&self, mut f: F) {
+        for node in self.chain().iter() {
+            f(&node.state());
+        }
+    }
+
+    /// Describes every listener still queued on the event, keyed listeners included, for dumping
+    /// who is parked on what when something hangs. The age of each listener is only recorded with
+    /// the `latency` feature.
+    pub fn debug_snapshot(&self) -> Vec<ListenerSnapshot> {
+        let chain = self.chain();
+        let mut snapshot: Vec<_> = chain.iter().map(Waker::snapshot).collect();
+        snapshot.extend(self.keyed_snapshot());
+        snapshot
+    }
+
+    /// Registers a callback invoked with the time between `listen` and the wake whenever a notify
+    /// wakes a live listener.
+    #[cfg(feature = "latency")]
+    pub fn on_wake(&self, f: impl Fn(Duration) + Send + Sync + 'static) {
+        self.on_wake.set(Arc::new(f));
+    }
+
+    /// Pops wakers until one of them is woken, skipping dropped listeners.
+    fn wake_next(&self) -> Option<WakeInfo> {
+        while let Some(node) = self.pop() {
+            if self.wake(&node) {
+                return Some(node.info());
+            }
+        }
+        None
+    }
+
+    /// Wakes the next live listener or stores a permit if there isn't one, returning the listener
+    /// that was woken.
+    fn wake_or_store(&self) -> Option<WakeInfo> {
+        loop {
+            if let Some(info) = self.wake_next() {
+                return Some(info);
+            }
+            let chain = self.chain();
+            if chain.is_empty() {
+                if !self.is_closed() {
+                    // a single permit, unlike notify_additional this doesn't accumulate
+                    if self.permits.load(Ordering::Relaxed) == 0 {
+                        self.permits.store(1, Ordering::Relaxed);
+                    }
+                }
+                return None;
+            }
+            // a listener registered after the chain was drained, it gets the notification
+        }
+    }
+
+    /// Wakes a waker claimed by [`Event::pop`] or [`Event::take_first`], false if it was dropped.
+    fn wake(&self, node: &Waker) -> bool {
+        let woken = self.wake_unpooled(node);
+        if woken {
+            self.recycle(node);
+        }
+        woken
+    }
+
+    /// Wakes every waker in `batch`, returning how many were live. A waker that panics (e.g. a
+    /// foreign `core::task::Waker`) doesn't stop the rest of the batch from being woken, the first
+    /// panic is kept in `panicked` for the caller to resume once it has finished up.
+    fn wake_batch<'w>(
+        &self,
+        batch: impl IntoIterator<Item = &'w Waker>,
+        panicked: &mut Option<Box<dyn Any + Send>>,
+    ) -> usize {
+        let mut woken = 0;
+        for node in batch {
+            if node.is_claimed() {
+                // a listener whose waker panicked was still claimed, it counts as woken
+                isolate_panic(panicked, || {
+                    self.wake(node);
+                });
+                woken += 1;
+            }
+        }
+        woken
+    }
+
+    /// [`Event::wake`] without keeping the listener's state for reuse.
+    fn wake_unpooled(&self, node: &Waker) -> bool {
+        if !node.is_claimed() {
+            return false;
+        }
+        trace!(id = node.key(), "wake");
+        node.unpark();
+        #[cfg(feature = "latency")]
+        if let Some(on_wake) = self.on_wake.get() {
+            on_wake(node.elapsed());
+        }
+        true
+    }
+
+    /// A listener state from a guard that has since been dropped, if the pool has one. Listeners
+    /// skip the pool rather than wait for it.
+    fn pooled_state(&self) -> Option<Arc<ListenerState>> {
+        let mut pool = self.pool.try_lock().ok()?;
+        // unique once the guard and the waker are both gone
+        let index = pool
+            .iter()
+            .position(|state| Arc::strong_count(state) == 1)?;
+        // pairs with the release of the last other reference, its writes to the state are done
+        portable_atomic::fence(Ordering::Acquire);
+        Some(pool.swap_remove(index))
+    }
+
+    /// Keeps the state of a woken listener so that it can be reused once its guard is dropped,
+    /// letting a thread that listens in a loop stop allocating.
+    fn recycle(&self, node: &Waker) {
+        let Ok(mut pool) = self.pool.try_lock() else {
+            return;
+        };
+        if pool.len() < MAX_POOL {
+            pool.extend(node.shared_state());
+        }
+    }
+
+    /// Returns a future that resolves once this event notifies it.
+    pub fn notified(&self) -> Listener<'_, T> {
+        Listener::new(self)
+    }
+
+    /// [`Event::notified`] for smol / async-io users, the future doesn't depend on any runtime.
+    #[cfg(feature = "async-io")]
+    pub fn notified_smol(&self) -> Listener<'_, T> {
+        self.notified()
+    }
+
+    /// Creates a listener that registers lazily, see [`EventListener`].
+    pub fn listener(&self) -> EventListener<'_, T> {
+        EventListener::new(self)
+    }
+
+    /// Creates a listener that coalesces notifications into a count between awaits.
+    pub fn batched(&self) -> BatchListener<'_, T> {
+        BatchListener::new(self)
+    }
+
+    fn notify_count(&self) -> usize {
+        self.notify_count.load(Ordering::SeqCst)
+    }
+
+    fn begin_notify(&self) -> usize {
+        self.begin_notify_with(Some(Ordering::SeqCst))
+    }
+
+    fn begin_notify_with(&self, fence: Option<Ordering>) -> usize {
+        if self.is_closed() {
+            return self.notify_count.load(Ordering::Relaxed);
+        }
+        // counted before the listeners are read so a batch listener registering concurrently
+        // either sees the new count or is in the chain to be woken
+        let count = self
+            .notify_count
+            .fetch_add(1, Ordering::SeqCst)
+            .wrapping_add(1);
+        // a relaxed fence orders nothing, std panics on one rather than ignoring it
+        if let Some(fence) = fence.filter(|&fence| fence != Ordering::Relaxed) {
+            portable_atomic::fence(fence);
+        }
+        hook_point!(self, Notify);
+        count
+    }
+
+    fn compact(&self, count: usize) {
+        if self.auto_compact != 0 && count.is_multiple_of(self.auto_compact) {
+            self.clear_dropped();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::waker::State;
+    use portable_atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    /// Leaves a dropped listener in the chain, as a guard that isn't linked to the chain (e.g. one
+    /// from an [`EventSet`]) does.
+    fn stale<T>(event: &Event<T>) {
+        let (waker, guard) = Waker::new();
+        event.push(waker);
+        drop(guard);
+    }
+
+    #[test]
+    fn dropped_guard_unlinks() {
+        let event = Event::default();
+        let live = event.listen();
+        for _ in 0..1000 {
+            drop(event.listen());
+            drop(event.listen_async(noop_waker()));
+            assert_eq!(event.chain().len(), 1);
+        }
+        drop(live);
+        assert_eq!(event.chain().len(), 0);
+        assert_eq!(event.listener_count(), 0);
+        assert_eq!(event.stale_listeners(), 0);
+
+        // the guard keeps the chain alive, not the event
+        let guard = Event::default().listen();
+        assert_eq!(guard.get_state(), State::Dropped);
+    }
+
+    #[test]
+    fn auto_traits() {
+        assert_send_sync::<Event>();
+        assert_send_sync::<Waker>();
+        assert_send_sync::<WaitGuard>();
+        assert_send_sync::<CompletedGuard<Notified>>();
+        assert_send_sync::<CompletedGuard<Cancelled>>();
+        assert_send_sync::<CompletedGuard<Closed>>();
+        assert_send_sync::<CompletedGuard<Full>>();
+        assert_send_sync::<PendingGuard>();
+        assert_send_sync::<State>();
+    }
+
+    #[test]
+    fn it_works() {
+        let event = Event::default();
+        let value = AtomicUsize::new(0);
+
+        thread::scope(|s| {
+            let jh = s.spawn(|| {
+                let guard = event.listen();
+                assert_eq!(value.load(Ordering::Acquire), 0);
+                assert_eq!(guard.get_state(), State::Waiting);
+                guard.wait();
+                assert_eq!(value.load(Ordering::Acquire), 42);
+                assert_eq!(guard.get_state(), State::Notified);
+            });
+            thread::sleep(Duration::from_millis(50));
+            value.store(42, Ordering::Release);
+            event.notify_one();
+
+            jh.join().expect("couldn't join!");
+        })
+    }
+    #[test]
+    fn drop_count() {
+        let event = Event::default();
+        let value = AtomicUsize::new(0);
+
+        thread::scope(|s| {
+            let jh = s.spawn(|| {
+                {
+                    let _guard_a = event.listen();
+                    let _guard_b = event.listen();
+                }
+                let guard_c = event.listen();
+                guard_c.wait();
+                assert_eq!(value.load(Ordering::Acquire), 42);
+            });
+            thread::sleep(Duration::from_millis(50));
+            value.store(42, Ordering::Release);
+            // the dropped guards unlinked themselves
+            assert_eq!(event.chain().len(), 1);
+            event.notify_one();
+            assert_eq!(event.chain().len(), 0);
+
+            jh.join().expect("couldn't join!");
+        })
+    }
+
+    /// Many rounds of "write the payload, notify, check the woken listener sees it". The payload
+    /// is published by the claim of the listener's state, this doesn't exercise the fence in
+    /// notify, which only matters for a listener registering concurrently.
+    #[test]
+    fn notify_publishes_payload() {
+        const ITERATIONS: usize = 10_000;
+        let event = Event::default();
+        let payload = AtomicUsize::new(0);
+        let seen = AtomicUsize::new(0);
+        let notified = AtomicUsize::new(0);
+
+        thread::scope(|s| {
+            let jh = s.spawn(|| {
+                for i in 1..=ITERATIONS {
+                    let guard = event.listen();
+                    if payload.load(Ordering::Relaxed) != i {
+                        guard.wait();
+                        assert_eq!(payload.load(Ordering::Relaxed), i);
+                    }
+                    // the notify for this round may still be popping, a listener registered for
+                    // the next round would be woken by it
+                    while notified.load(Ordering::Acquire) != i {
+                        thread::yield_now();
+                    }
+                    seen.store(i, Ordering::Release);
+                }
+            });
+            for i in 1..=ITERATIONS {
+                payload.store(i, Ordering::Relaxed);
+                if i % 2 == 0 {
+                    event.notify_one();
+                } else {
+                    event.notify_all();
+                }
+                notified.store(i, Ordering::Release);
+                while seen.load(Ordering::Acquire) != i {
+                    thread::yield_now();
+                }
+            }
+            jh.join().expect("couldn't join!");
+        })
+    }
+
+    /// Dekker style: the producer stores the flag then notifies, the consumer listens then checks
+    /// the flag, a lost wakeup times the round out. The fence in notify is what rules that out,
+    /// but only hardware that reorders the flag store past the listener count load can tell. On
+    /// x86 the locked notify count increment already orders them, so this can't catch a missing
+    /// fence there.
+    #[test]
+    fn notify_sees_concurrent_listener() {
+        const ROUNDS: usize = 20_000;
+        let event = Event::default();
+        let round = AtomicUsize::new(0);
+        let done = AtomicUsize::new(0);
+
+        thread::scope(|s| {
+            s.spawn(|| {
+                for i in 1..=ROUNDS {
+                    let deadline = Instant::now() + Duration::from_secs(5);
+                    assert_eq!(
+                        event.wait_timeout_while(deadline, || round.load(Ordering::Relaxed) < i),
+                        Ok(()),
+                        "lost the wakeup for round {i}"
+                    );
+                    done.store(i, Ordering::Release);
+                }
+            });
+            for i in 1..=ROUNDS {
+                round.store(i, Ordering::Release);
+                event.notify_one();
+                while done.load(Ordering::Acquire) != i {
+                    std::hint::spin_loop();
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn listen_sweeps_dropped() {
+        let event = Event::default();
+        let guard = event.listen();
+        for _ in 0..1000 {
+            stale(&event);
+        }
+        assert!(event.chain().len() <= MIN_SWEEP);
+        // mostly live listeners are left alone
+        let live: Vec<_> = (0..MIN_SWEEP).map(|_| event.listen()).collect();
+        let len = event.chain().len();
+        stale(&event);
+        assert_eq!(event.chain().len(), len + 1);
+        assert_eq!(event.notify_all(), live.len() + 1);
+        assert_eq!(guard.get_state(), State::Notified);
+    }
+
+    #[test]
+    fn auto_compact() {
+        let event = Event::with_auto_compact(3);
+        let live: Vec<_> = (0..9).map(|_| event.listen()).collect();
+        for round in 1..=9 {
+            for _ in 0..4 {
+                stale(&event);
+            }
+            event.notify_one();
+            assert_eq!(live[round - 1].get_state(), State::Notified);
+            assert_eq!(event.chain().len(), 9 - round + 4 * (round % 3));
+        }
+        assert_eq!(event.num_listeners().load(Ordering::Relaxed), 0);
+    }
+
+    #[cfg(feature = "latency")]
+    #[test]
+    fn on_wake_latency() {
+        let event = Event::default();
+        let (sender, recv) = std::sync::mpsc::channel();
+        event.on_wake(move |latency| sender.send(latency).expect("send failed"));
+
+        let guard = event.listen();
+        thread::sleep(Duration::from_millis(10));
+        event.notify_one();
+        assert_eq!(guard.get_state(), State::Notified);
+        let latency = recv.try_recv().expect("callback didn't fire");
+        assert!(latency >= Duration::from_millis(10));
+        assert!(latency < Duration::from_secs(10));
+
+        drop(event.listen());
+        event.notify_one();
+        assert!(recv.try_recv().is_err());
+    }
+
+    #[test]
+    fn notify_skips_dropped() {
+        let event = Event::default();
+        stale(&event);
+        let live = event.listen();
+        event.notify_one();
+        assert_eq!(live.get_state(), State::Notified);
+
+        let (mut waker, guard) = Waker::new();
+        waker.set_affinity(1);
+        event.push(waker);
+        drop(guard);
+        let live = event.listen_with_affinity(1);
+        event.notify_one_local(1);
+        assert_eq!(live.get_state(), State::Notified);
+        // the dropped slot is skipped over rather than popped
+        assert_eq!(event.clear_dropped(), 1);
+    }
+
+    #[test]
+    fn permit_mode() {
+        let event = Event::default();
+        event.enable_permit_mode();
+        event.notify_one();
+        event.notify_one();
+
+        let guard = event.listen();
+        guard.wait();
+        assert_eq!(guard.get_state(), State::Notified);
+        assert_eq!(event.chain().len(), 0);
+
+        // the permit is a single bit, the second notify didn't store another one
+        let guard = event.listen();
+        assert_eq!(guard.get_state(), State::Waiting);
+        event.notify_one();
+        assert_eq!(guard.get_state(), State::Notified);
+        assert_eq!(event.permits.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn listener_count_tracks_chain() {
+        let event = Event::default();
+        let guards: Vec<_> = (0..4).map(|_| event.listen()).collect();
+        drop(guards);
+        event.clear_dropped();
+        let _local = event.listen_with_affinity(0);
+        event.notify_one_local(0);
+        let _guard = event.listen();
+        event.notify_all();
+        event.check_listeners(&event.chain());
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "listener count diverged from the chain")]
+    fn corrupted_listener_count() {
+        let event = Event::default();
+        event.num_listeners().fetch_add(1, Ordering::Relaxed);
+        event.notify_one();
+    }
+
+    #[test]
+    fn high_water() {
+        let event = Event::default();
+        let fired = Arc::new(AtomicUsize::new(0));
+        let counter = fired.clone();
+        event.set_high_water(3, move |count| {
+            assert_eq!(count, 3);
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+        let guards: Vec<_> = (0..5).map(|_| event.listen()).collect();
+        assert_eq!(fired.load(Ordering::Relaxed), 1);
+
+        event.notify_all();
+        let _guards: Vec<_> = (0..3).map(|_| event.listen()).collect();
+        assert_eq!(fired.load(Ordering::Relaxed), 2);
+        drop(guards);
+    }
+
+    #[test]
+    fn notify_all_except() {
+        let event = Event::default();
+        let first = event.listen();
+        let middle = event.listen();
+        let last = event.listen();
+        assert_eq!(event.notify_all_except(middle.id()), 2);
+        assert_eq!(first.get_state(), State::Notified);
+        assert_eq!(middle.get_state(), State::Waiting);
+        assert_eq!(last.get_state(), State::Notified);
+        assert_eq!(event.chain().len(), 1);
+    }
+
+    #[test]
+    fn notify_filtered() {
+        const READER: u64 = 0;
+        const WRITER: u64 = 1;
+        let event = Event::default();
+        let readers: Vec<_> = (0..2).map(|_| event.listen_tagged(READER)).collect();
+        let writer = event.listen_tagged(WRITER);
+        let untagged = event.listen();
+        drop(event.listen_tagged(READER));
+        assert_eq!(event.notify_filtered(|tag| tag == READER), 2);
+        assert!(readers.iter().all(|g| g.get_state() == State::Notified));
+        assert_eq!(writer.get_state(), State::Waiting);
+        assert_eq!(untagged.get_state(), State::Waiting);
+        assert_eq!(event.notify_filtered(|tag| tag == READER), 0);
+        assert_eq!(event.notify_filtered(|_| true), 1);
+        assert_eq!(writer.get_state(), State::Notified);
+        assert_eq!(event.chain().len(), 1);
+    }
+
+    #[test]
+    fn wait_for_listeners() {
+        let event = &Event::default();
+        let ready = AtomicUsize::new(0);
+        thread::scope(|s| {
+            for _ in 0..2 {
+                let ready = &ready;
+                s.spawn(move || {
+                    thread::sleep(Duration::from_millis(10));
+                    let guard = event.listen();
+                    ready.fetch_add(1, Ordering::SeqCst);
+                    guard.wait();
+                });
+            }
+            event.wait_for_listeners(2);
+            assert_eq!(event.chain().len(), 2);
+            event.notify_all();
+        });
+        assert_eq!(ready.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn wait_for_listeners_async() {
+        let event = Event::new_shared();
+        let consumer = event.clone();
+        let jh = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            consumer.notified().await
+        });
+        event.wait_for_listeners_async(1).await;
+        event.notify_one();
+        assert_eq!(jh.await.expect("consumer panicked"), State::Notified);
+    }
+
+    #[test]
+    fn notify_or_store() {
+        let event = Event::default();
+        let guard = event.listen();
+        assert_eq!(event.notify_or_store(), NotifyResult::Woke(1));
+        assert_eq!(guard.get_state(), State::Notified);
+
+        drop(event.listen());
+        assert_eq!(event.notify_or_store(), NotifyResult::Stored);
+        assert_eq!(event.pending_notifications(), 1);
+        assert_eq!(event.listen().get_state(), State::Notified);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn traces_wake() {
+        #[derive(Default)]
+        struct Recorder(Mutex<Vec<String>>);
+
+        impl tracing::field::Visit for &Recorder {
+            fn record_debug(
+                &mut self,
+                field: &tracing::field::Field,
+                value: &dyn core::fmt::Debug,
+            ) {
+                if field.name() == "message" {
+                    self.0.lock().unwrap().push(format!("{value:?}"));
+                }
+            }
+        }
+
+        impl tracing::Subscriber for Recorder {
+            fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, _: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+                tracing::span::Id::from_u64(1)
+            }
+            fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+            fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+            fn event(&self, event: &tracing::Event<'_>) {
+                event.record(&mut &*self);
+            }
+            fn enter(&self, _: &tracing::span::Id) {}
+            fn exit(&self, _: &tracing::span::Id) {}
+        }
+
+        let recorder = Arc::new(Recorder::default());
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let event = Event::default();
+            let _guard = event.listen();
+            event.notify_one();
+        });
+        let messages = recorder.0.lock().unwrap();
+        assert_eq!(*messages, ["listen", "notify_one", "wake"]);
+    }
+
+    #[test]
+    fn notify_one_detailed() {
+        let event = Event::default();
+        assert_eq!(event.notify_one_detailed(), None);
+
+        let sync = event.listen();
+        let task = event.listen_async(noop_waker());
+        let info = event.notify_one_detailed().expect("no listener woken");
+        assert_eq!(info.id, sync.id());
+        assert_eq!(info.kind, WakerKind::Sync);
+        let info = event.notify_one_detailed().expect("no listener woken");
+        assert_eq!(info.id, task.id());
+        assert_eq!(info.kind, WakerKind::Async);
+        #[cfg(feature = "latency")]
+        assert!(info.latency > Duration::ZERO);
+    }
+
+    fn noop_waker() -> core::task::Waker {
+        struct Noop;
+        impl std::task::Wake for Noop {
+            fn wake(self: Arc<Self>) {}
+        }
+        Arc::new(Noop).into()
+    }
+
+    #[test]
+    fn close() {
+        let event = &Event::default();
+        thread::scope(|s| {
+            let jh = s.spawn(move || {
+                let guard = event.listen();
+                guard.wait();
+                guard.get_state()
+            });
+            while event.chain().is_empty() {
+                thread::sleep(Duration::from_millis(1));
+            }
+            event.close();
+            assert_eq!(jh.join().expect("couldn't join!"), State::Closed);
+        });
+
+        assert!(event.is_closed());
+        assert_eq!(event.try_listen().err(), Some(ListenError::Closed));
+        assert_eq!(event.listen().wait_checked(), Err(WaitError::Closed));
+        let deadline = Instant::now() + Duration::from_secs(1);
+        assert_eq!(
+            event.listen().wait_deadline(deadline),
+            Err(WaitError::Closed)
+        );
+        assert_eq!(event.listen().get_state(), State::Closed);
+        event.enable_permit_mode();
+        event.notify_one();
+        assert_eq!(event.pending_notifications(), 0);
+        assert_eq!(event.notify_count(), 0);
+    }
+
+    #[test]
+    fn tickets() {
+        let event = Event::default();
+        let guards: Vec<_> = (0..3).map(|_| event.listen()).collect();
+        assert!(guards.windows(2).all(|w| w[0].ticket() < w[1].ticket()));
+        event.notify_one();
+        let states: Vec<_> = guards.iter().map(WaitGuard::get_state).collect();
+        assert_eq!(states, [State::Notified, State::Waiting, State::Waiting]);
+
+        guards[0].wait();
+        assert!(event.rearm(&guards[0]));
+        assert!(guards[0].ticket() > guards[2].ticket());
+    }
+
+    #[test]
+    fn notify_all_reconciles_count() {
+        let event = Event::default();
+        let guards: Vec<_> = (0..2).map(|_| event.listen()).collect();
+        event.num_listeners().fetch_add(3, Ordering::Relaxed);
+        event.notify_all();
+        assert!(guards.iter().all(|g| g.get_state() == State::Notified));
+        assert_eq!(event.num_listeners().load(Ordering::Relaxed), 0);
+        assert!(event.chain().is_empty());
+    }
+
+    #[test]
+    fn notify_one_reason() {
+        let event = Event::default();
+        assert_eq!(event.notify_one_reason(), NotifyOutcome::NoListeners);
+        stale(&event);
+        stale(&event);
+        assert_eq!(event.notify_one_reason(), NotifyOutcome::AllDropped);
+        let guard = event.listen();
+        assert_eq!(event.notify_one_reason(), NotifyOutcome::Woke);
+        assert_eq!(guard.get_state(), State::Notified);
+    }
+
+    #[test]
+    fn wait_while() {
+        let event = &Event::default();
+        let counter = &AtomicUsize::new(0);
+        thread::scope(|s| {
+            s.spawn(move || {
+                for _ in 0..3 {
+                    thread::sleep(Duration::from_millis(2));
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    event.notify_all();
+                }
+            });
+            assert_eq!(
+                event.wait_while(|| counter.load(Ordering::SeqCst) < 3),
+                Ok(())
+            );
+        });
+        event.close();
+        assert_eq!(event.wait_while(|| true), Err(WaitError::Closed));
+        assert_eq!(event.wait_while(|| false), Ok(()));
+    }
+
+    #[test]
+    fn wait_timeout_while() {
+        let event = &Event::default();
+        let counter = &AtomicUsize::new(0);
+        let deadline = Instant::now() + Duration::from_millis(500);
+        thread::scope(|s| {
+            s.spawn(move || {
+                for _ in 0..5 {
+                    thread::sleep(Duration::from_millis(5));
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    event.notify_all();
+                }
+            });
+            assert_eq!(
+                event.wait_timeout_while(deadline, || counter.load(Ordering::SeqCst) < 5),
+                Ok(())
+            );
+        });
+
+        let deadline = Instant::now() + Duration::from_millis(20);
+        assert_eq!(
+            event.wait_timeout_while(deadline, || counter.load(Ordering::SeqCst) < 6),
+            Err(WaitError::Timeout)
+        );
+        assert!(Instant::now() >= deadline);
+    }
+
+    #[test]
+    fn wait_until() {
+        let event = &Event::default();
+        let counter = &AtomicUsize::new(0);
+        thread::scope(|s| {
+            s.spawn(move || {
+                for _ in 0..5 {
+                    thread::sleep(Duration::from_millis(5));
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    event.notify_all();
+                }
+            });
+            assert_eq!(
+                event.wait_until(|| counter.load(Ordering::SeqCst) == 5),
+                Ok(())
+            );
+        });
+        event.close();
+        assert_eq!(
+            event.wait_until(|| counter.load(Ordering::SeqCst) == 6),
+            Err(WaitError::Closed)
+        );
+    }
+
+    #[tokio::test]
+    async fn wait_until_async() {
+        let event = Arc::new(Event::default());
+        let counter = Arc::new(AtomicUsize::new(0));
+        let (notifier, count) = (event.clone(), counter.clone());
+        let jh = tokio::spawn(async move {
+            for _ in 0..5 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                count.fetch_add(1, Ordering::SeqCst);
+                notifier.notify_all();
+            }
+        });
+        assert_eq!(
+            event
+                .wait_until_async(|| counter.load(Ordering::SeqCst) == 5)
+                .await,
+            Ok(())
+        );
+        jh.await.expect("notifier panicked");
+        event.close();
+        assert_eq!(
+            event.wait_until_async(|| false).await,
+            Err(WaitError::Closed)
+        );
+    }
+
+    #[tokio::test]
+    async fn full_event_fails_fast() {
+        let event = Event::bounded(0);
+        let deadline = Instant::now() + Duration::from_secs(10);
+        assert_eq!(event.wait_while(|| true), Err(WaitError::Full));
+        assert_eq!(event.wait_until(|| false), Err(WaitError::Full));
+        assert_eq!(
+            event.wait_timeout_while(deadline, || true),
+            Err(WaitError::Full)
+        );
+        assert_eq!(event.wait_until_async(|| false).await, Err(WaitError::Full));
+        assert_eq!(event.recv().await, Err(RecvError::Full));
+        let guard = event.listen();
+        assert_eq!(guard.try_wait(), Err(WaitError::Full));
+        assert_eq!(guard.wait_checked(), Err(WaitError::Full));
+        assert_eq!(guard.wait_deadline(deadline), Err(WaitError::Full));
+        assert!(matches!(
+            event.listen().wait_outcome(),
+            Err(Unnotified::Full(_))
+        ));
+        // none of them parked until the deadline
+        assert!(Instant::now() < deadline);
+    }
+
+    #[test]
+    fn listen_typed() {
+        let event = Event::default();
+        let pending = event.listen_typed();
+        assert_eq!(event.listener_count(), 1);
+        event.notify_one();
+        let completed = pending.wait().unwrap();
+        assert_eq!(completed.outcome(), State::Notified);
+        let pending = event.listen_typed();
+        event.close();
+        assert_eq!(pending.wait().unwrap_err().outcome(), State::Closed);
+    }
+
+    #[test]
+    fn notify_one_with_ordering() {
+        let event = &Event::default();
+        let data = &AtomicUsize::new(0);
+        let ready = &portable_atomic::AtomicBool::new(false);
+        thread::scope(|s| {
+            let jh = s.spawn(move || {
+                let guard = event.listen();
+                ready.store(true, Ordering::SeqCst);
+                guard.wait();
+                // the data is published through `ready`, not the notification
+                while !ready.load(Ordering::Acquire) {}
+                data.load(Ordering::Relaxed)
+            });
+            while !ready.swap(false, Ordering::SeqCst) {
+                thread::yield_now();
+            }
+            data.store(42, Ordering::Relaxed);
+            ready.store(true, Ordering::Release);
+            // the listener registered before `ready` was set, it is seen without the fence
+            assert!(event.notify_one_with_ordering(Ordering::Relaxed, None));
+            assert_eq!(jh.join().expect("couldn't join!"), 42);
+        });
+
+        let guard = event.listen();
+        assert!(event.notify_one_with_ordering(Ordering::SeqCst, Some(Ordering::SeqCst)));
+        assert_eq!(guard.get_state(), State::Notified);
+        assert!(!event.notify_one_with_ordering(Ordering::SeqCst, Some(Ordering::SeqCst)));
+
+        let guard = event.listen();
+        assert!(event.notify_one_with_ordering(Ordering::Release, Some(Ordering::Relaxed)));
+        assert_eq!(guard.get_state(), State::Notified);
+    }
+
+    #[test]
+    fn notify_relaxed() {
+        let event = &Event::default();
+        let data = &AtomicUsize::new(0);
+        thread::scope(|s| {
+            let jh = s.spawn(move || {
+                let guard = event.listen();
+                guard.wait();
+                data.load(Ordering::Relaxed)
+            });
+            // the listener is known to be registered so the fence isn't needed
+            event.wait_for_listeners(1);
+            data.store(7, Ordering::Relaxed);
+            assert!(event.notify_one_relaxed());
+            assert_eq!(jh.join().expect("couldn't join!"), 7);
+        });
+        assert!(!event.notify_one_relaxed());
+
+        let guards: Vec<_> = (0..3).map(|_| event.listen()).collect();
+        assert_eq!(event.notify_all_relaxed(), 3);
+        assert!(guards.iter().all(|g| g.get_state() == State::Notified));
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn assert_balanced() {
+        let event = Event::default();
+        let guard = event.listen();
+        drop(event.listen());
+        event.clear_dropped();
+        event.assert_balanced();
+        event.notify_one();
+        assert_eq!(guard.get_state(), State::Notified);
+        event.assert_balanced();
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "2 listeners counted but only 1 are waiting")]
+    fn assert_unbalanced() {
+        let event = Event::default();
+        let _guard = event.listen();
+        stale(&event);
+        event.assert_balanced();
+    }
+
+    #[test]
+    fn notify_seq() {
+        let event = Event::default();
+        let behind = event.listen_at(0);
+        let ahead = event.listen_at(1);
+        let plain = event.listen();
+        assert_eq!(event.notify_seq(1), 2);
+        assert_eq!(behind.get_state(), State::Notified);
+        assert_eq!(ahead.get_state(), State::Waiting);
+        assert_eq!(plain.get_state(), State::Notified);
+
+        // the sequence doesn't go backwards, so this isn't an advance
+        let plain = event.listen();
+        assert_eq!(event.notify_seq(0), 0);
+        assert_eq!(plain.get_state(), State::Waiting);
+
+        event.notify_seq(3);
+        assert_eq!(ahead.get_state(), State::Notified);
+        let late = event.listen_at(2);
+        assert_eq!(late.get_state(), State::Notified);
+        late.wait();
+    }
+
+    #[test]
+    fn notify_one_or_timeout() {
+        let event = &Event::default();
+        thread::scope(|s| {
+            let jh = s.spawn(move || {
+                thread::sleep(Duration::from_millis(20));
+                let guard = event.listen();
+                guard.wait();
+                guard.get_state()
+            });
+            assert!(event.notify_one_or_timeout(Duration::from_millis(100)));
+            assert_eq!(jh.join().expect("couldn't join!"), State::Notified);
+        });
+
+        let start = Instant::now();
+        assert!(!event.notify_one_or_timeout(Duration::from_millis(20)));
+        assert!(start.elapsed() >= Duration::from_millis(20));
+
+        let start = Instant::now();
+        thread::scope(|s| {
+            s.spawn(move || {
+                thread::sleep(Duration::from_millis(20));
+                event.close();
+            });
+            assert!(!event.notify_one_or_timeout(Duration::from_secs(10)));
+        });
+        assert!(!event.notify_one_or_timeout(Duration::from_secs(10)));
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn notify_one_or_timeout_permit() {
+        let event = &Event::default();
+        event.enable_permit_mode();
+        let start = Instant::now();
+        thread::scope(|s| {
+            let jh = s.spawn(move || {
+                thread::sleep(Duration::from_millis(20));
+                let guard = event.listen();
+                guard.wait();
+                guard.get_state()
+            });
+            assert!(event.notify_one_or_timeout(Duration::from_secs(10)));
+            assert_eq!(jh.join().expect("couldn't join!"), State::Notified);
+        });
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert_eq!(event.pending_notifications(), 0);
+    }
+
+    #[test]
+    fn notify_n() {
+        let event = Event::default();
+        let first = event.listen();
+        drop(event.listen());
+        let guards: Vec<_> = (0..3).map(|_| event.listen()).collect();
+        assert_eq!(event.notify(3), 3);
+        assert_eq!(first.get_state(), State::Notified);
+        assert_eq!(guards[0].get_state(), State::Notified);
+        assert_eq!(guards[1].get_state(), State::Notified);
+        assert_eq!(guards[2].get_state(), State::Waiting);
+        assert_eq!(event.notify(5), 1);
+        assert_eq!(event.notify(0), 0);
+    }
+
+    #[test]
+    fn notify_one_reports_woken() {
+        let event = Event::default();
+        assert!(!event.notify_one());
+        drop(event.listen());
+        assert!(!event.notify_one());
+        let guard = event.listen();
+        assert!(event.notify_one());
+        assert_eq!(guard.get_state(), State::Notified);
+    }
+
+    #[test]
+    fn notify_last() {
+        let event = Event::default();
+        assert!(!event.notify_last());
+        let guards: Vec<_> = (0..3).map(|_| event.listen()).collect();
+        drop(event.listen());
+        assert!(event.notify_last());
+        assert_eq!(guards[2].get_state(), State::Notified);
+        assert!(event.notify_one());
+        assert_eq!(guards[0].get_state(), State::Notified);
+        assert_eq!(guards[1].get_state(), State::Waiting);
+    }
+
+    #[test]
+    fn priority_listeners() {
+        let event = Event::default();
+        let low = event.listen();
+        let high = event.listen_with_priority(2);
+        let mid = event.listen_with_priority(1);
+        let also_high = event.listen_with_priority(2);
+        // a dropped listener never outranks a live one
+        drop(event.listen_with_priority(3));
+        assert!(event.notify_one());
+        assert_eq!(high.get_state(), State::Notified);
+        assert_eq!(also_high.get_state(), State::Waiting);
+        assert!(event.notify_last());
+        assert_eq!(also_high.get_state(), State::Notified);
+        assert!(event.notify_one());
+        assert_eq!(mid.get_state(), State::Notified);
+        assert!(event.notify_one());
+        assert_eq!(low.get_state(), State::Notified);
+        assert!(!event.notify_one());
+        assert!(event.chain().is_empty());
+    }
+
+    #[test]
+    fn panicking_waker_isolated() {
+        struct Panics;
+        impl std::task::Wake for Panics {
+            fn wake(self: Arc<Self>) {
+                panic!("foreign waker");
+            }
+        }
+        struct Counts(AtomicUsize);
+        impl std::task::Wake for Counts {
+            fn wake(self: Arc<Self>) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+        let event = Event::default();
+        let counts = Arc::new(Counts(AtomicUsize::new(0)));
+        let _first = event.listen_async(Arc::new(Panics).into());
+        let second = event.listen_async(counts.clone().into());
+        let result = std::panic::catch_unwind(|| event.notify_all());
+        assert!(result.is_err());
+        assert_eq!(second.get_state(), State::Notified);
+        assert_eq!(counts.0.load(Ordering::SeqCst), 1);
+        assert!(event.chain().is_empty());
+        // the event is still usable afterwards
+        let guard = event.listen();
+        assert_eq!(event.notify_all(), 1);
+        assert_eq!(guard.get_state(), State::Notified);
+    }
+
+    #[test]
+    fn notify_all_snapshot() {
+        let event = Event::default();
+        let guards: Vec<_> = (0..3).map(|_| event.listen()).collect();
+        drop(event.listen());
+        assert_eq!(event.notify_all_snapshot(), 3);
+        assert!(guards.iter().all(|g| g.get_state() == State::Notified));
+        assert!(event.chain().is_empty());
+        let later = event.listen();
+        assert_eq!(later.get_state(), State::Waiting);
+        assert_eq!(event.notify_all_snapshot(), 1);
+    }
+
+    #[test]
+    fn notify_all_count() {
+        let event = Event::default();
+        assert_eq!(event.notify_all(), 0);
+        let guards: Vec<_> = (0..3).map(|_| event.listen()).collect();
+        drop(event.listen());
+        assert_eq!(event.notify_all(), 3);
+        assert!(guards.iter().all(|g| g.get_state() == State::Notified));
+        assert_eq!(event.notify_all(), 0);
+    }
+
+    #[test]
+    fn debug_snapshot() {
+        let event = Event::default();
+        let guard = event.listen();
+        let task = event.listen_async(noop_waker());
+        drop(event.listen());
+        stale(&event);
+        let keyed = event.listen_with_key(1);
+        let snapshot = event.debug_snapshot();
+        let states: Vec<_> = snapshot.iter().map(|l| (l.id, l.kind, l.state)).collect();
+        assert_eq!(states[0], (guard.id(), WakerKind::Sync, State::Waiting));
+        assert_eq!(states[1], (task.id(), WakerKind::Async, State::Waiting));
+        assert_eq!(states[2].2, State::Dropped);
+        assert_eq!(states[3], (keyed.id(), WakerKind::Sync, State::Waiting));
+        assert_eq!(states.len(), 4);
+    }
+
+    #[test]
+    fn listen_loop_reuses_state() {
+        let event = Event::default();
+        let first = event.listen();
+        let id = first.id();
+        event.notify_one();
+        first.wait();
+        drop(first);
+        for _ in 0..10 {
+            let guard = event.listen();
+            assert_eq!(guard.id(), id);
+            assert_eq!(guard.get_state(), State::Waiting);
+            event.notify_one();
+            guard.wait();
+            assert_eq!(guard.get_state(), State::Notified);
+        }
+        assert_eq!(event.pool.lock().unwrap().len(), 1);
+        // a guard that is still alive keeps its state out of the pool
+        let held = event.listen();
+        event.notify_one();
+        assert_ne!(event.listen().id(), held.id());
+    }
+
+    #[test]
+    fn epoch() {
+        let event = Event::default();
+        let epoch = event.epoch();
+        assert!(!event.is_notified_since(epoch));
+        // counted even with nobody to wake
+        event.notify_all();
+        assert!(event.is_notified_since(epoch));
+        let epoch = event.epoch();
+        let _guard = event.listen();
+        assert!(!event.is_notified_since(epoch));
+        event.notify_one();
+        assert!(event.is_notified_since(epoch));
+        let epoch = event.epoch();
+        event.close();
+        assert_eq!(event.epoch(), epoch);
+        assert!(event.is_notified_since(epoch));
+    }
+
+    #[test]
+    fn reset() {
+        let event = Event::default();
+        assert_eq!(event.notify_additional(3), 0);
+        assert_eq!(event.reset(false), 3);
+        assert_eq!(event.pending_notifications(), 0);
+        stale(&event);
+        let guard = event.listen();
+        assert_eq!(event.chain().len(), 2);
+        assert_eq!(guard.get_state(), State::Waiting);
+        assert_eq!(event.reset(true), 0);
+        assert_eq!(event.chain().len(), 1);
+        event.notify_one();
+        assert_eq!(guard.get_state(), State::Notified);
+    }
+
+    #[test]
+    fn notify_additional() {
+        let event = Event::default();
+        let guard = event.listen();
+        assert_eq!(event.notify_additional(3), 1);
+        assert_eq!(guard.get_state(), State::Notified);
+        assert_eq!(event.notify_additional(1), 0);
+        assert_eq!(event.pending_notifications(), 3);
+
+        let guards: Vec<_> = (0..4).map(|_| event.listen()).collect();
+        let states: Vec<_> = guards.iter().map(WaitGuard::get_state).collect();
+        assert_eq!(
+            states,
+            [
+                State::Notified,
+                State::Notified,
+                State::Notified,
+                State::Waiting
+            ]
+        );
+        assert_eq!(event.pending_notifications(), 0);
+    }
+
+    #[test]
+    fn static_event() {
+        static EVENT: Event = Event::new();
+        thread::scope(|s| {
+            let jh = s.spawn(|| {
+                let guard = EVENT.listen();
+                guard.wait();
+                guard.get_state()
+            });
+            while EVENT.chain().is_empty() {
+                thread::sleep(Duration::from_millis(1));
+            }
+            EVENT.notify_one();
+            assert_eq!(jh.join().expect("couldn't join!"), State::Notified);
+        });
+    }
+
+    #[test]
+    fn introspection() {
+        let event = Event::default();
+        assert!(!event.has_listeners());
+        let _guard = event.listen();
+        stale(&event);
+        assert!(event.has_listeners());
+        assert_eq!(event.listener_count(), 2);
+        assert_eq!(event.stale_listeners(), 1);
+        event.clear_dropped();
+        assert_eq!(event.listener_count(), 1);
+        assert_eq!(event.stale_listeners(), 0);
+    }
+
+    #[test]
+    fn pending_notifications() {
+        let event = Event::default();
+        event.notify_one();
+        assert_eq!(event.pending_notifications(), 0);
+
+        event.enable_permit_mode();
+        event.notify_one();
+        assert_eq!(event.pending_notifications(), 1);
+        event.notify_one();
+        assert_eq!(event.pending_notifications(), 1);
+
+        let guard = event.listen();
+        assert_eq!(guard.get_state(), State::Notified);
+        assert_eq!(event.pending_notifications(), 0);
+    }
+
+    #[test]
+    fn notify_one_local() {
+        let event = Event::default();
+        let far = event.listen_with_affinity(0);
+        let near = event.listen_with_affinity(1);
+        event.notify_one_local(1);
+        assert_eq!(near.get_state(), State::Notified);
+        assert_eq!(far.get_state(), State::Waiting);
+
+        event.notify_one_local(1);
+        assert_eq!(far.get_state(), State::Notified);
+        assert_eq!(event.chain().len(), 0);
+    }
+
+    #[test]
+    fn notify_all_strict_order() {
+        struct Tagged {
+            tag: usize,
+            log: Arc<Mutex<Vec<usize>>>,
+        }
+
+        impl std::task::Wake for Tagged {
+            fn wake(self: Arc<Self>) {
+                self.log.lock().expect("poisoned").push(self.tag);
+            }
+        }
+
+        const LISTENERS: usize = 2_000;
+        let event = Event::default();
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let done = portable_atomic::AtomicBool::new(false);
+
+        thread::scope(|s| {
+            let notifiers: Vec<_> = (0..2)
+                .map(|_| {
+                    s.spawn(|| {
+                        while !done.load(Ordering::Acquire) {
+                            event.notify_all_strict();
+                            thread::yield_now();
+                        }
+                    })
+                })
+                .collect();
+            let guards: Vec<_> = (0..LISTENERS)
+                .map(|tag| {
+                    let waker = Arc::new(Tagged {
+                        tag,
+                        log: log.clone(),
+                    });
+                    event.listen_async(waker.into())
+                })
+                .collect();
+            done.store(true, Ordering::Release);
+            for notifier in notifiers {
+                notifier.join().expect("couldn't join!");
+            }
+            event.notify_all_strict();
+            drop(guards);
+        });
+
+        let log = log.lock().expect("poisoned");
+        assert_eq!(log.len(), LISTENERS);
+        assert!(log.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn for_each_listener() {
+        let event = Event::default();
+        let _live: Vec<_> = (0..3).map(|_| event.listen()).collect();
+        stale(&event);
+        stale(&event);
+
+        let (mut waiting, mut dropped) = (0, 0);
+        event.for_each_listener(|state| match state {
+            State::Waiting => waiting += 1,
+            State::Dropped => dropped += 1,
+            State::Notified | State::Closed => unreachable!("finished listeners leave the chain"),
+            State::Full => unreachable!("never registered"),
+        });
+        assert_eq!((waiting, dropped), (3, 2));
+        assert_eq!(event.chain().len(), 5);
+    }
+
+    #[test]
+    fn notify_one_fairness() {
+        const THREADS: usize = 4;
+        const NOTIFIES: usize = 10_000;
+        let event = Event::default();
+        let stop = portable_atomic::AtomicBool::new(false);
+        let counts: Vec<_> = (0..THREADS).map(|_| AtomicUsize::new(0)).collect();
+
+        thread::scope(|s| {
+            for count in &counts {
+                let (event, stop) = (&event, &stop);
+                s.spawn(move || loop {
+                    let guard = event.listen();
+                    if stop.load(Ordering::Acquire) {
+                        break;
+                    }
+                    guard.wait();
+                    if stop.load(Ordering::Acquire) {
+                        break;
+                    }
+                    count.fetch_add(1, Ordering::Relaxed);
+                });
+            }
+            for _ in 0..NOTIFIES {
+                while event.num_listeners().load(Ordering::Acquire) < THREADS {
+                    thread::yield_now();
+                }
+                event.notify_one();
+            }
+            stop.store(true, Ordering::Release);
+            event.notify_all();
+        });
+
+        let expected = NOTIFIES / THREADS;
+        for count in &counts {
+            let count = count.load(Ordering::Relaxed);
+            assert!(count.abs_diff(expected) <= expected / 10, "unfair: {count}");
+        }
+    }
+
+    #[test]
+    fn shared_event() {
+        fn notify(event: impl AsRef<Event>) {
+            event.as_ref().notify_one();
+        }
+
+        let event = Event::new_shared();
+        let listening = event.clone();
+        let (sender, recv) = std::sync::mpsc::channel();
+        let jh = thread::spawn(move || {
+            let guard = listening.listen();
+            sender.send(()).expect("send failed");
+            guard.wait();
+            guard.get_state()
+        });
+        recv.recv().expect("recv failed");
+        notify(event.clone());
+        assert_eq!(jh.join().expect("couldn't join!"), State::Notified);
+        notify(&*event);
+    }
+
+    #[test]
+    fn notify_all_and_wait() {
+        let event = &Event::default();
+        let (sender, recv) = std::sync::mpsc::channel();
+        thread::scope(|s| {
+            for _ in 0..2 {
+                let sender = sender.clone();
+                s.spawn(move || {
+                    let guard = event.listen();
+                    sender.send(()).expect("send failed");
+                    guard.wait();
+                });
+            }
+            recv.recv().expect("recv failed");
+            recv.recv().expect("recv failed");
+            assert_eq!(event.notify_all_and_wait(Duration::from_secs(5)), Ok(2));
+        });
+    }
+
+    #[test]
+    fn notify_all_and_wait_timeout() {
+        let event = &Event::default();
+        let (sender, recv) = std::sync::mpsc::channel();
+        thread::scope(|s| {
+            let fast = sender.clone();
+            s.spawn(move || {
+                let guard = event.listen();
+                fast.send(()).expect("send failed");
+                guard.wait();
+            });
+            s.spawn(move || {
+                let guard = event.listen();
+                sender.send(()).expect("send failed");
+                thread::sleep(Duration::from_millis(200));
+                guard.wait();
+            });
+            recv.recv().expect("recv failed");
+            recv.recv().expect("recv failed");
+            assert_eq!(
+                event.notify_all_and_wait(Duration::from_millis(50)),
+                Err(WaitError::AckTimeout { acked: 1 })
+            );
+        });
+    }
+
+    #[test]
+    fn rearm() {
+        let event = Event::default();
+        let guard = event.listen();
+        assert!(!event.rearm(&guard));
+        event.notify_one();
+        guard.wait();
+
+        assert!(event.rearm(&guard));
+        assert_eq!(guard.get_state(), State::Waiting);
+        thread::scope(|s| {
+            s.spawn(|| {
+                while event.chain().is_empty() {
+                    thread::sleep(Duration::from_millis(1));
+                }
+                event.notify_one();
+            });
+            guard.wait();
+        });
+        assert_eq!(guard.get_state(), State::Notified);
+
+        let (waker, cancelled) = Waker::new();
+        drop(waker);
+        assert!(!cancelled.try_rearm());
+    }
+
+    #[test]
+    fn clear_dropped() {
+        let event = Event::default();
+        let _live = event.listen();
+        stale(&event);
+        stale(&event);
+        assert_eq!(event.clear_dropped(), 2);
+        assert_eq!(event.chain().len(), 1);
+        assert_eq!(event.num_listeners().load(Ordering::Relaxed), 1);
     }
 }
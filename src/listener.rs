@@ -0,0 +1,355 @@
+use crate::{Event, State, WaitGuard};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+/// Future resolving once the [`Event`] notifies it. Registers on first poll and works with any
+/// executor.
+#[derive(Debug)]
+pub struct Listener<'a, T = ()> {
+    event: &'a Event<T>,
+    registration: Option<(WaitGuard, core::task::Waker)>,
+}
+
+impl<'a, T> Listener<'a, T> {
+    pub(crate) fn new(event: &'a Event<T>) -> Self {
+        Self {
+            event,
+            registration: None,
+        }
+    }
+}
+
+/// Polls an existing registration, `None` if there isn't one yet. A registration that is still
+/// waiting is switched over to wake `cx`'s task in place rather than registering the task again,
+/// so a future polled from different tasks never has more than one waker in the chain.
+pub(crate) fn update_registration(
+    registration: &mut Option<(WaitGuard, core::task::Waker)>,
+    cx: &mut Context<'_>,
+) -> Option<Poll<State>> {
+    let (guard, waker) = registration.as_mut()?;
+    if guard.get_state() == State::Waiting && !waker.will_wake(cx.waker()) {
+        guard.set_async_waker(cx.waker());
+        waker.clone_from(cx.waker());
+    }
+    // claims happen with the chain locked, one that beat the switch is visible here
+    Some(match guard.get_state() {
+        State::Waiting => Poll::Pending,
+        state => Poll::Ready(state),
+    })
+}
+
+impl<T> Future for Listener<'_, T> {
+    type Output = State;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        if let Some(state) = update_registration(&mut this.registration, cx) {
+            return state;
+        }
+        let guard = this.event.listen_async(cx.waker().clone());
+        // a permit or a closed event completes the registration straight away, nothing will wake us
+        let registered = guard.get_state();
+        this.registration = Some((guard, cx.waker().clone()));
+        match registered {
+            State::Waiting => Poll::Pending,
+            state => Poll::Ready(state),
+        }
+    }
+}
+
+/// A listener that only registers with its [`Event`] once it is waited on or polled.
+///
+/// Any notify made after the listener was created completes it, including one made before it
+/// registered, so it can be created before checking a condition and waited on afterwards without
+/// losing a notification in between. The flip side is that a notify meant for another listener can
+/// complete it too, so the condition should be checked again.
+#[derive(Debug)]
+pub struct EventListener<'a, T = ()> {
+    event: &'a Event<T>,
+    created: usize,
+    registration: Option<(WaitGuard, core::task::Waker)>,
+}
+
+impl<'a, T> EventListener<'a, T> {
+    pub(crate) fn new(event: &'a Event<T>) -> Self {
+        Self {
+            event,
+            created: event.notify_count(),
+            registration: None,
+        }
+    }
+
+    fn notified_since_created(&self) -> bool {
+        self.event.notify_count() != self.created
+    }
+
+    pub fn is_registered(&self) -> bool {
+        self.registration.is_some()
+    }
+
+    /// Registers the current thread and blocks until the event is notified, returning straight
+    /// away if it already has been since the listener was created.
+    pub fn wait(self) -> State {
+        let guard = self.event.listen();
+        // checked after registering, a notify in between found us in the chain
+        if self.notified_since_created() {
+            return State::Notified;
+        }
+        guard.wait();
+        guard.get_state()
+    }
+}
+
+impl<T> Future for EventListener<'_, T> {
+    type Output = State;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.notified_since_created() {
+            self.registration = None;
+            return Poll::Ready(State::Notified);
+        }
+        let this = &mut *self;
+        if let Some(state) = update_registration(&mut this.registration, cx) {
+            return state;
+        }
+        let guard = self.event.listen_async(cx.waker().clone());
+        let state = guard.get_state();
+        self.registration = Some((guard, cx.waker().clone()));
+        if self.notified_since_created() {
+            self.registration = None;
+            return Poll::Ready(State::Notified);
+        }
+        match state {
+            State::Waiting => Poll::Pending,
+            state => Poll::Ready(state),
+        }
+    }
+}
+
+impl WaitGuard {
+    /// Polls the guard from async code, e.g. inside [`core::future::poll_fn`]. The guard's
+    /// registration is switched over to wake `cx`'s task (registering it again with the event it
+    /// came from if it isn't in the chain) so that any guard, including one from
+    /// [`Event::listen`], can be awaited without a [`Listener`].
+    pub fn register_and_poll(&self, cx: &mut Context<'_>) -> Poll<State> {
+        let state = self.get_state();
+        if state != State::Waiting {
+            return Poll::Ready(state);
+        }
+        self.set_async_waker(cx.waker());
+        // claims happen with the chain locked, one that beat us is visible here
+        match self.get_state() {
+            State::Waiting => Poll::Pending,
+            state => Poll::Ready(state),
+        }
+    }
+}
+
+/// Why [`Event::recv`] stopped receiving.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecvError {
+    /// The event has been closed.
+    Closed,
+    /// The event is bounded and full, see [`State::Full`].
+    Full,
+}
+
+impl core::fmt::Display for RecvError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RecvError::Closed => write!(f, "event closed"),
+            RecvError::Full => write!(f, "event full"),
+        }
+    }
+}
+
+impl<T> Event<T> {
+    /// Waits for the next notification, failing once the event is closed so that
+    /// `while event.recv().await.is_ok()` loops end on shutdown.
+    pub async fn recv(&self) -> Result<(), RecvError> {
+        match self.notified().await {
+            State::Notified => Ok(()),
+            State::Full => Err(RecvError::Full),
+            State::Waiting | State::Dropped | State::Closed => Err(RecvError::Closed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn notify_when_listening(event: &Event) {
+        while event.chain().is_empty() {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        event.notify_one();
+    }
+
+    #[tokio::test]
+    async fn notified() {
+        let event = Arc::new(Event::default());
+        let notifier = event.clone();
+        let jh = std::thread::spawn(move || notify_when_listening(&notifier));
+        assert_eq!(event.notified().await, State::Notified);
+        jh.join().expect("couldn't join!");
+        assert!(event.chain().is_empty());
+    }
+
+    #[tokio::test]
+    async fn recv_until_closed() {
+        let event = Event::new_shared();
+        let producer = event.clone();
+        let jh = tokio::spawn(async move {
+            for _ in 0..3 {
+                producer.wait_for_listeners_async(1).await;
+                producer.notify_one();
+            }
+            producer.wait_for_listeners_async(1).await;
+            producer.close();
+        });
+        let mut received = 0;
+        let err = loop {
+            match event.recv().await {
+                Ok(()) => received += 1,
+                Err(err) => break err,
+            }
+        };
+        assert_eq!((received, err), (3, RecvError::Closed));
+        jh.await.expect("producer panicked");
+        assert_eq!(event.recv().await, Err(RecvError::Closed));
+    }
+
+    #[tokio::test]
+    async fn register_and_poll() {
+        let event = Event::new_shared();
+        let guard = event.listen();
+        let notifier = event.clone();
+        let jh = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            notifier.notify_one();
+        });
+        let state = core::future::poll_fn(|cx| guard.register_and_poll(cx)).await;
+        assert_eq!(state, State::Notified);
+        jh.await.expect("notifier panicked");
+        assert!(event.chain().is_empty());
+    }
+
+    #[tokio::test]
+    async fn register_and_poll_keyed() {
+        let event = Event::new_shared();
+        let guard = event.listen_with_key(7);
+        let notifier = event.clone();
+        let jh = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            assert_eq!(notifier.notify_key(7), 1);
+        });
+        let state = core::future::poll_fn(|cx| {
+            let state = guard.register_and_poll(cx);
+            // switched in place in the key index, never queued
+            assert!(event.chain().is_empty());
+            state
+        })
+        .await;
+        assert_eq!(state, State::Notified);
+        jh.await.expect("notifier panicked");
+    }
+
+    #[test]
+    fn event_listener_sees_earlier_notify() {
+        let event = Event::default();
+        let listener = event.listener();
+        assert!(!listener.is_registered());
+        assert!(event.chain().is_empty());
+        event.notify_one();
+        assert_eq!(listener.wait(), State::Notified);
+    }
+
+    #[tokio::test]
+    async fn event_listener_future() {
+        let event = Event::new_shared();
+        let listener = event.listener();
+        let notifier = event.clone();
+        let jh = std::thread::spawn(move || notify_when_listening(&notifier));
+        assert_eq!(listener.await, State::Notified);
+        jh.join().expect("couldn't join!");
+
+        let listener = event.listener();
+        event.notify_all();
+        assert_eq!(listener.await, State::Notified);
+    }
+
+    #[tokio::test]
+    async fn notified_consumes_permit() {
+        let event = Event::default();
+        event.enable_permit_mode();
+        event.notify_one();
+        assert_eq!(event.notified().await, State::Notified);
+        assert_eq!(event.pending_notifications(), 0);
+
+        assert_eq!(event.notify_or_store(), crate::NotifyResult::Stored);
+        assert_eq!(event.listener().await, State::Notified);
+    }
+
+    #[test]
+    fn repolled_from_other_task_keeps_one_registration() {
+        struct Noop;
+        impl std::task::Wake for Noop {
+            fn wake(self: Arc<Self>) {}
+        }
+        let event = Event::default();
+        let mut listener = Box::pin(event.notified());
+        for _ in 0..3 {
+            // a distinct waker each time, as if the future moved between tasks
+            let waker = Arc::new(Noop).into();
+            let mut cx = Context::from_waker(&waker);
+            assert_eq!(listener.as_mut().poll(&mut cx), Poll::Pending);
+            assert_eq!(event.listener_count(), 1);
+        }
+        event.notify_one();
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(
+            listener.as_mut().poll(&mut cx),
+            Poll::Ready(State::Notified)
+        );
+    }
+
+    #[test]
+    fn dropped_before_notify() {
+        let event = Event::default();
+        let mut listener = Box::pin(event.notified());
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(listener.as_mut().poll(&mut cx), Poll::Pending);
+        drop(listener);
+        assert!(event.chain().is_empty());
+        assert_eq!(event.clear_dropped(), 0);
+    }
+
+    fn noop_waker() -> core::task::Waker {
+        struct Noop;
+        impl std::task::Wake for Noop {
+            fn wake(self: Arc<Self>) {}
+        }
+        Arc::new(Noop).into()
+    }
+
+    #[cfg(feature = "async-io")]
+    #[test]
+    fn smol_task() {
+        let event = Arc::new(Event::default());
+        smol::block_on(async {
+            let listening = event.clone();
+            let task = smol::spawn(async move { listening.notified_smol().await });
+            let notifier = event.clone();
+            let jh = std::thread::spawn(move || notify_when_listening(&notifier));
+            assert_eq!(task.await, State::Notified);
+            jh.join().expect("couldn't join!");
+        });
+        assert!(event.chain().is_empty());
+    }
+}
@@ -0,0 +1,237 @@
+use crate::waker::{self, State, WaitError, Waited, Waker};
+use crate::Event;
+use crate::WaitGuard;
+use core::marker::PhantomPinned;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use portable_atomic::AtomicU8;
+use std::sync::atomic::Ordering;
+use std::time::Instant;
+
+/// Listener state owned by the caller, usually pinned on the stack, so that registering with an
+/// [`Event`] doesn't allocate.
+///
+/// The node unlinks itself from the event when it is dropped. It can be registered again once
+/// it has been notified.
+///
+/// ```
+/// # use wake_me::{Event, ListenerNode, State};
+/// let event = Event::default();
+/// let mut node = std::pin::pin!(ListenerNode::new());
+/// event.listen_pinned(node.as_mut());
+/// event.notify_one();
+/// node.wait();
+/// assert_eq!(node.get_state(), State::Notified);
+/// ```
+#[derive(Debug)]
+pub struct ListenerNode<'a> {
+    state: AtomicU8,
+    event: Option<&'a Event>,
+    _pinned: PhantomPinned,
+}
+
+impl Default for ListenerNode<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for ListenerNode<'_> {
+    fn drop(&mut self) {
+        let _ = self.state.compare_exchange(
+            State::Waiting as u8,
+            State::Dropped as u8,
+            Ordering::Release,
+            Ordering::Relaxed,
+        );
+        self.unlink();
+    }
+}
+
+impl<'a> ListenerNode<'a> {
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicU8::new(State::Dropped as u8),
+            event: None,
+            _pinned: PhantomPinned,
+        }
+    }
+
+    fn unlink(&mut self) {
+        if let Some(event) = self.event.take() {
+            event.unlink(waker::key(&self.state));
+        }
+    }
+
+    pub fn wait(&self) {
+        waker::wait(&self.state);
+    }
+
+    pub fn wait_deadline(&self, deadline: Instant) -> Result<Waited, WaitError> {
+        let remaining = waker::wait_deadline(&self.state, deadline)?;
+        Ok(Waited {
+            state: self.get_state(),
+            remaining,
+        })
+    }
+
+    pub fn get_state(&self) -> State {
+        waker::get_state(&self.state)
+    }
+}
+
+impl Event {
+    /// Registers caller owned storage with the event. Any previous registration of the node is
+    /// removed first.
+    pub fn listen_pinned<'a>(&'a self, node: Pin<&mut ListenerNode<'a>>) {
+        // SAFETY: the node is never moved out of, only its fields are updated in place
+        let node = unsafe { node.get_unchecked_mut() };
+        node.unlink();
+        node.state.store(State::Waiting as u8, Ordering::Release);
+        node.event = Some(self);
+        // SAFETY: the node is pinned so its state stays put, and it unlinks itself from this event
+        // before it is dropped
+        let waker = unsafe { Waker::new_pinned(&node.state) };
+        self.push(waker);
+    }
+
+    /// Polls `node` from async code without allocating, e.g. from a hand written future that keeps
+    /// its node pinned inside itself. The first poll registers the node to wake `cx`'s task, later
+    /// polls switch it over to the current task until the node is notified.
+    pub fn poll_pinned<'a>(
+        &'a self,
+        node: Pin<&mut ListenerNode<'a>>,
+        cx: &mut Context<'_>,
+    ) -> Poll<State> {
+        // SAFETY: as in listen_pinned
+        let node = unsafe { node.get_unchecked_mut() };
+        if node.event.is_none() {
+            node.state.store(State::Waiting as u8, Ordering::Release);
+            node.event = Some(self);
+            // SAFETY: as in listen_pinned
+            let waker = unsafe { Waker::new_pinned_async(&node.state, cx.waker().clone()) };
+            self.push(waker);
+        } else if node.get_state() == State::Waiting {
+            let key = waker::key(&node.state);
+            if let Some(waker) = self.chain().iter_mut().find(|waker| waker.key() == key) {
+                waker.set_async(cx.waker());
+            }
+        }
+        // claims happen with the chain locked, one that beat us is visible here
+        match node.get_state() {
+            State::Waiting => Poll::Pending,
+            state => Poll::Ready(state),
+        }
+    }
+}
+
+impl<T> Event<T> {
+    /// Removes every waker for the listener identified by `key` from the chain.
+    pub(crate) fn unlink(&self, key: usize) {
+        self.chain.get().unlink(key);
+    }
+
+    /// Drops `guard`, removing its registration straight away. The same as dropping it, which
+    /// unlinks a guard that is still waiting from the chain (or the key index, see
+    /// [`Event::listen_with_key`]).
+    pub fn deregister(&self, guard: WaitGuard) {
+        drop(guard);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::pin::pin;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn notified_across_threads() {
+        let event = Event::default();
+        std::thread::scope(|s| {
+            let jh = s.spawn(|| {
+                let mut node = pin!(ListenerNode::new());
+                for _ in 0..3 {
+                    event.listen_pinned(node.as_mut());
+                    node.wait();
+                    assert_eq!(node.get_state(), State::Notified);
+                }
+            });
+            for _ in 0..3 {
+                while event.chain().is_empty() {
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+                event.notify_one();
+            }
+            jh.join().expect("couldn't join!");
+        });
+        assert!(event.chain().is_empty());
+    }
+
+    #[test]
+    fn drop_unlinks() {
+        let event = Event::default();
+        {
+            let mut node = pin!(ListenerNode::new());
+            event.listen_pinned(node.as_mut());
+            assert_eq!(node.get_state(), State::Waiting);
+            assert_eq!(event.chain().len(), 1);
+        }
+        assert!(event.chain().is_empty());
+        assert_eq!(event.num_listeners().load(Ordering::Relaxed), 0);
+        event.notify_one();
+    }
+
+    #[test]
+    fn deregister() {
+        let event = Event::default();
+        let guard = event.listen();
+        let keyed = event.listen_with_key(1);
+        let _other = event.listen();
+        event.deregister(guard);
+        event.deregister(keyed);
+        assert_eq!(event.chain().len(), 1);
+        assert_eq!(event.notify_key(1), 0);
+        #[cfg(debug_assertions)]
+        event.assert_balanced();
+    }
+
+    #[tokio::test]
+    async fn poll_pinned() {
+        let event = Arc::new(Event::default());
+        let notifier = event.clone();
+        let jh = tokio::spawn(async move {
+            notifier.wait_for_listeners_async(1).await;
+            notifier.notify_one();
+        });
+        let mut node = pin!(ListenerNode::new());
+        let state = core::future::poll_fn(|cx| event.poll_pinned(node.as_mut(), cx)).await;
+        assert_eq!(state, State::Notified);
+        jh.await.expect("notifier panicked");
+        assert!(event.chain().is_empty());
+    }
+
+    #[test]
+    fn poll_pinned_dropped_unlinks() {
+        let event = Event::default();
+        {
+            let mut node = pin!(ListenerNode::new());
+            let waker = std::task::Waker::noop();
+            let mut cx = Context::from_waker(waker);
+            assert_eq!(event.poll_pinned(node.as_mut(), &mut cx), Poll::Pending);
+            assert_eq!(event.poll_pinned(node.as_mut(), &mut cx), Poll::Pending);
+            assert_eq!(event.chain().len(), 1);
+        }
+        assert!(event.chain().is_empty());
+    }
+
+    #[test]
+    fn relisten_replaces_registration() {
+        let event = Event::default();
+        let mut node = pin!(ListenerNode::new());
+        event.listen_pinned(node.as_mut());
+        event.listen_pinned(node.as_mut());
+        assert_eq!(event.chain().len(), 1);
+    }
+}
@@ -0,0 +1,58 @@
+//! Parking for the sync wait path. Uses `std::thread::park` by default or `parking_lot_core`
+//! keyed on the address of the listener state with the `parking-lot` feature.
+
+use portable_atomic::AtomicU8;
+use std::thread::Thread;
+use std::time::Duration;
+
+#[cfg(not(feature = "parking-lot"))]
+pub fn park(_state: &AtomicU8) {
+    std::thread::park();
+}
+
+#[cfg(not(feature = "parking-lot"))]
+pub fn park_timeout(_state: &AtomicU8, timeout: Duration) {
+    std::thread::park_timeout(timeout);
+}
+
+#[cfg(not(feature = "parking-lot"))]
+pub fn unpark(thread: &Thread, _key: usize) {
+    thread.unpark();
+}
+
+#[cfg(feature = "parking-lot")]
+fn park_until(state: &AtomicU8, deadline: Option<std::time::Instant>) {
+    use crate::waker::State;
+    use std::sync::atomic::Ordering;
+
+    // SAFETY: the validate, before sleep and timed out callbacks don't panic or call into
+    // parking_lot_core
+    unsafe {
+        parking_lot_core::park(
+            crate::waker::key(state),
+            || State::from(state.load(Ordering::Acquire)) == State::Waiting,
+            || {},
+            |_, _| {},
+            parking_lot_core::DEFAULT_PARK_TOKEN,
+            deadline,
+        );
+    }
+}
+
+#[cfg(feature = "parking-lot")]
+pub fn park(state: &AtomicU8) {
+    park_until(state, None);
+}
+
+#[cfg(feature = "parking-lot")]
+pub fn park_timeout(state: &AtomicU8, timeout: Duration) {
+    park_until(state, std::time::Instant::now().checked_add(timeout));
+}
+
+#[cfg(feature = "parking-lot")]
+pub fn unpark(_thread: &Thread, key: usize) {
+    // SAFETY: the key is only used for parking on listener state and the callback is trivial
+    unsafe {
+        parking_lot_core::unpark_one(key, |_| parking_lot_core::DEFAULT_UNPARK_TOKEN);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::waker::ListenerState;
+use crate::{Event, State, WaitGuard};
+use core::task::Poll;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, MutexGuard, PoisonError};
+
+impl<T> Event<T> {
+    pub(crate) fn payloads(&self) -> MutexGuard<'_, Vec<(Arc<ListenerState>, T)>> {
+        self.payloads.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub(crate) fn permit_value(&self) -> MutexGuard<'_, Option<T>> {
+        self.permit_value
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Wakes the next live listener (see [`Event::notify_one`]) and hands it `value`, which it
+    /// gets back from [`WaitGuard::wait_value`], [`Event::take_value`] or
+    /// [`Event::notified_value`]. The value is given back if nobody was woken. In permit mode (see
+    /// [`Event::enable_permit_mode`]) the value is stored with the permit instead and handed to the
+    /// listener that consumes it, unless another value is already waiting there.
+    ///
+    /// ```
+    /// # use wake_me::Event;
+    /// let event = Event::<u32>::with_payload();
+    /// assert_eq!(event.notify_one_with(1), Err(1));
+    /// let guard = event.listen();
+    /// assert_eq!(event.notify_one_with(2), Ok(()));
+    /// assert_eq!(guard.wait_value(&event), Some(2));
+    /// ```
+    pub fn notify_one_with(&self, value: T) -> Result<(), T> {
+        let count = self.begin_notify();
+        let result = self.hand_over(value);
+        self.compact(count);
+        result
+    }
+
+    fn hand_over(&self, value: T) -> Result<(), T> {
+        loop {
+            // held across the claim so a guard that sees itself notified finds its value
+            let mut payloads = self.payloads();
+            // values nobody is left to take
+            payloads.retain(|(state, _)| Arc::strong_count(state) > 1);
+            while let Some(node) = self.pop() {
+                if !node.is_claimed() {
+                    continue;
+                }
+                if let Some(state) = node.shared_state() {
+                    payloads.push((state, value));
+                }
+                drop(payloads);
+                // pooling the state would keep it alive past its guard, which the prune relies on
+                self.wake_unpooled(&node);
+                return Ok(());
+            }
+            drop(payloads);
+            if !self.permit_mode.load(Ordering::Relaxed) {
+                return Err(value);
+            }
+            let chain = self.chain();
+            if chain.is_empty() {
+                let mut permit_value = self.permit_value();
+                if self.is_closed() || permit_value.is_some() {
+                    return Err(value);
+                }
+                // a single permit, the value rides on one that is already stored
+                if self.permits.load(Ordering::Relaxed) == 0 {
+                    self.permits.store(1, Ordering::Relaxed);
+                }
+                *permit_value = Some(value);
+                return Ok(());
+            }
+            // a listener registered after the chain was drained, it gets the value
+        }
+    }
+
+    /// Takes the value handed to `guard` by [`Event::notify_one_with`], if there is one.
+    pub fn take_value(&self, guard: &WaitGuard) -> Option<T> {
+        let mut payloads = self.payloads();
+        let index = payloads
+            .iter()
+            .position(|(state, _)| Arc::ptr_eq(state, guard.shared_state()))?;
+        Some(payloads.swap_remove(index).1)
+    }
+
+    /// Waits for a notification and returns the value it carried. `None` if the listener was
+    /// woken without one, e.g. by [`Event::notify_one`] or because the event was closed.
+    pub async fn notified_value(&self) -> Option<T> {
+        let mut guard: Option<WaitGuard> = None;
+        core::future::poll_fn(|cx| match &guard {
+            Some(guard) => guard.register_and_poll(cx),
+            None => {
+                let registered = self.listen_async(cx.waker().clone());
+                let state = registered.get_state();
+                guard = Some(registered);
+                match state {
+                    State::Waiting => Poll::Pending,
+                    state => Poll::Ready(state),
+                }
+            }
+        })
+        .await;
+        self.take_value(guard.as_ref()?)
+    }
+}
+
+impl WaitGuard {
+    /// Blocks until notified and returns the value handed over by
+    /// [`Event::notify_one_with`], see [`Event::notified_value`].
+    pub fn wait_value<T>(&self, event: &Event<T>) -> Option<T> {
+        self.wait();
+        event.take_value(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn value_across_threads() {
+        let event = &Event::<String>::with_payload();
+        thread::scope(|s| {
+            let jh = s.spawn(move || event.listen().wait_value(event));
+            event.wait_for_listeners(1);
+            event
+                .notify_one_with("done".to_string())
+                .expect("a listener is registered");
+            assert_eq!(jh.join().expect("couldn't join!").as_deref(), Some("done"));
+        });
+        assert!(event.payloads().is_empty());
+    }
+
+    #[test]
+    fn skips_dropped_listeners() {
+        let event = Event::<u32>::with_payload();
+        let guard = event.listen();
+        drop(event.listen());
+        let newest = event.listen();
+        event.notify_one_with(1).expect("a listener is registered");
+        assert_eq!(event.take_value(&newest), None);
+        assert_eq!(event.take_value(&guard), Some(1));
+        event.notify_one_with(2).expect("a listener is registered");
+        assert_eq!(newest.wait_value(&event), Some(2));
+        assert_eq!(event.notify_one_with(3), Err(3));
+    }
+
+    #[test]
+    fn untaken_value_released() {
+        let event = Event::<Arc<()>>::with_payload();
+        let value = Arc::new(());
+        drop(event.listen());
+        event
+            .notify_one_with(value.clone())
+            .expect_err("only a dropped listener");
+        let guard = event.listen();
+        event
+            .notify_one_with(value.clone())
+            .expect("a listener is registered");
+        drop(guard);
+        assert_eq!(Arc::strong_count(&value), 2);
+        event
+            .notify_one_with(value.clone())
+            .expect_err("no listener");
+        assert_eq!(Arc::strong_count(&value), 1);
+    }
+
+    #[tokio::test]
+    async fn notified_value() {
+        let event = Arc::new(Event::<u32>::with_payload());
+        let notifier = event.clone();
+        let jh = tokio::spawn(async move {
+            notifier.wait_for_listeners_async(1).await;
+            notifier
+                .notify_one_with(7)
+                .expect("a listener is registered");
+        });
+        assert_eq!(event.notified_value().await, Some(7));
+        jh.await.expect("notifier panicked");
+
+        event.close();
+        assert_eq!(event.notified_value().await, None);
+    }
+
+    #[test]
+    fn value_waits_with_permit() {
+        let event = Event::<u32>::with_payload();
+        event.enable_permit_mode();
+        assert_eq!(event.notify_one_with(1), Ok(()));
+        assert_eq!(event.pending_notifications(), 1);
+        // the permit already carries a value
+        assert_eq!(event.notify_one_with(2), Err(2));
+        let guard = event.listen();
+        assert_eq!(guard.get_state(), State::Notified);
+        assert_eq!(event.take_value(&guard), Some(1));
+        assert_eq!(event.pending_notifications(), 0);
+
+        assert_eq!(event.notify_one_with(3), Ok(()));
+        event.close();
+        assert_eq!(event.notify_one_with(4), Err(4));
+        assert!(event.permit_value().is_none());
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{Event, State, WaitGuard};
+
+/// A registration made by [`Event::prepare_listen`] that hasn't been waited on yet.
+///
+/// Any notify from the moment it was prepared reaches it, so the caller can check its condition
+/// and then either [`PreparedListen::commit`] to park or [`PreparedListen::cancel`] if there is
+/// nothing to wait for. Dropping it cancels.
+///
+/// ```
+/// # use wake_me::{Event, State};
+/// # use std::sync::atomic::{AtomicBool, Ordering};
+/// let ready = AtomicBool::new(true);
+/// let event = Event::default();
+/// let prepared = event.prepare_listen();
+/// if ready.load(Ordering::SeqCst) {
+///     prepared.cancel();
+/// } else {
+///     assert_eq!(prepared.commit(), State::Notified);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct PreparedListen<'a, T = ()> {
+    event: &'a Event<T>,
+    guard: Option<WaitGuard>,
+}
+
+impl<T> Event<T> {
+    /// Reserves a place in the chain for the current thread, the first half of a two-phase listen.
+    pub fn prepare_listen(&self) -> PreparedListen<'_, T> {
+        PreparedListen {
+            event: self,
+            guard: Some(self.listen()),
+        }
+    }
+}
+
+impl<T> PreparedListen<'_, T> {
+    /// Parks until notified, returning straight away if that already happened.
+    pub fn commit(mut self) -> State {
+        let guard = self.guard.take().expect("only taken by commit or cancel");
+        guard.wait();
+        guard.get_state()
+    }
+
+    /// Removes the reservation. A notification it already received is handed on to the next
+    /// listener so it isn't lost.
+    pub fn cancel(mut self) {
+        self.release();
+    }
+
+    pub fn get_state(&self) -> State {
+        self.guard
+            .as_ref()
+            .map_or(State::Dropped, WaitGuard::get_state)
+    }
+
+    fn release(&mut self) {
+        let Some(guard) = self.guard.take() else {
+            return;
+        };
+        // listeners are only claimed with the chain locked, once unlinked the state is final
+        self.event.unlink(guard.id());
+        if guard.get_state() == State::Notified {
+            self.event.notify_one();
+        }
+    }
+}
+
+impl<T> Drop for PreparedListen<'_, T> {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use portable_atomic::AtomicBool;
+    use std::sync::atomic::Ordering;
+    use std::thread;
+
+    #[test]
+    fn cancel_removes_reservation() {
+        let event = Event::default();
+        let prepared = event.prepare_listen();
+        assert_eq!(event.listener_count(), 1);
+        prepared.cancel();
+        assert!(event.chain().is_empty());
+    }
+
+    #[test]
+    fn cancel_forwards_notification() {
+        let event = Event::default();
+        let prepared = event.prepare_listen();
+        let other = event.listen();
+        event.notify_one();
+        assert_eq!(prepared.get_state(), State::Notified);
+        drop(prepared);
+        assert_eq!(other.get_state(), State::Notified);
+    }
+
+    #[test]
+    fn commit_sees_notify_after_prepare() {
+        let event = &Event::default();
+        let ready = &AtomicBool::new(false);
+        thread::scope(|s| {
+            let jh = s.spawn(move || loop {
+                let prepared = event.prepare_listen();
+                if ready.load(Ordering::SeqCst) {
+                    prepared.cancel();
+                    return;
+                }
+                prepared.commit();
+            });
+            ready.store(true, Ordering::SeqCst);
+            event.notify_all();
+            jh.join().expect("couldn't join!");
+        });
+    }
+}
@@ -0,0 +1,241 @@
+use crate::waker::Waker;
+use crate::{Event, State, WaitGuard};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::Poll;
+use portable_atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+/// Waits on several [`Event`]s at once, either for whichever of them fires first
+/// ([`EventSet::wait_any`]) or for all of them ([`EventSet::wait_all`]).
+///
+/// For wait-any a single guard is registered with every event and the registrations that lost
+/// are removed afterwards.
+///
+/// ```
+/// # use wake_me::{Event, EventSet, State};
+/// let data = Event::default();
+/// let shutdown = Event::default();
+/// let set = EventSet::new([&data, &shutdown]);
+/// std::thread::scope(|s| {
+///     s.spawn(|| {
+///         shutdown.wait_for_listeners(1);
+///         shutdown.notify_all();
+///     });
+///     assert_eq!(set.wait_any(), (1, State::Notified));
+/// });
+/// assert_eq!(data.listener_count(), 0);
+/// ```
+#[derive(Debug)]
+pub struct EventSet<'a> {
+    events: Vec<&'a Event>,
+}
+
+/// One guard registered with every event in a set, unlinked from all of them when dropped.
+struct Registration<'s, 'a> {
+    set: &'s EventSet<'a>,
+    guard: WaitGuard,
+    winner: Arc<AtomicUsize>,
+}
+
+impl<'a> EventSet<'a> {
+    pub fn new(events: impl IntoIterator<Item = &'a Event>) -> Self {
+        Self {
+            events: events.into_iter().collect(),
+        }
+    }
+
+    /// Blocks until one of the events notifies (or closes), returning its index in the set and
+    /// the state it finished the listener in.
+    pub fn wait_any(&self) -> (usize, State) {
+        let registration = Registration::new(self, Waker::for_guard);
+        registration.guard.wait();
+        registration.finish()
+    }
+
+    /// Async version of [`EventSet::wait_any`].
+    pub async fn notified_any(&self) -> (usize, State) {
+        let mut registration: Option<Registration<'_, 'a>> = None;
+        core::future::poll_fn(|cx| {
+            let registration = match &registration {
+                Some(registration) => {
+                    if registration.guard.get_state() == State::Waiting {
+                        for event in &self.events {
+                            // the guard is queued with every event, not just its own
+                            event
+                                .chain
+                                .get()
+                                .set_async(registration.guard.id(), cx.waker());
+                        }
+                    }
+                    registration
+                }
+                None => registration.insert(Registration::new(self, |guard| {
+                    Waker::for_guard_async(guard, cx.waker().clone())
+                })),
+            };
+            // claims happen with the chain locked, one that beat us is visible here
+            match registration.guard.get_state() {
+                State::Waiting => Poll::Pending,
+                _ => Poll::Ready(()),
+            }
+        })
+        .await;
+        registration.expect("registered on the first poll").finish()
+    }
+}
+
+impl EventSet<'_> {
+    /// Blocks until every event in the set has notified (or closed) a listener registered by this
+    /// call, for sequencing on several dependencies. Returns the state each listener finished in,
+    /// in set order.
+    pub fn wait_all(&self) -> Vec<State> {
+        let guards: Vec<_> = self.events.iter().map(|event| event.listen()).collect();
+        guards
+            .iter()
+            .map(|guard| {
+                guard.wait();
+                guard.get_state()
+            })
+            .collect()
+    }
+
+    /// Async version of [`EventSet::wait_all`], registering on the first poll.
+    pub async fn notified_all(&self) -> Vec<State> {
+        let mut listeners: Vec<_> = self.events.iter().map(|event| event.notified()).collect();
+        let mut states = vec![None; listeners.len()];
+        core::future::poll_fn(|cx| {
+            for (listener, state) in listeners.iter_mut().zip(&mut states) {
+                if state.is_none() {
+                    if let Poll::Ready(done) = Pin::new(listener).poll(cx) {
+                        *state = Some(done);
+                    }
+                }
+            }
+            if states.iter().all(Option::is_some) {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+        states.into_iter().flatten().collect()
+    }
+}
+
+impl<'s, 'a> Registration<'s, 'a> {
+    fn new(set: &'s EventSet<'a>, waker: impl Fn(&WaitGuard) -> Waker) -> Self {
+        let guard = WaitGuard::new(Arc::default());
+        let winner = Arc::new(AtomicUsize::new(usize::MAX));
+        for (index, event) in set.events.iter().enumerate() {
+            let mut waker = waker(&guard);
+            waker.report_claim(winner.clone(), index);
+            event.push(waker);
+            // completed by a permit or a closed event, the rest needn't bother
+            if guard.get_state() != State::Waiting {
+                break;
+            }
+        }
+        Self { set, guard, winner }
+    }
+
+    fn finish(self) -> (usize, State) {
+        // the winner is recorded just after the claim so it may not be visible quite yet
+        let winner = loop {
+            match self.winner.load(Ordering::Acquire) {
+                usize::MAX => core::hint::spin_loop(),
+                index => break index,
+            }
+        };
+        (winner, self.guard.get_state())
+    }
+}
+
+impl Drop for Registration<'_, '_> {
+    fn drop(&mut self) {
+        for event in &self.set.events {
+            event.unlink(self.guard.id());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn losers_cleaned_up() {
+        let events = [Event::default(), Event::default(), Event::default()];
+        let set = EventSet::new(&events);
+        thread::scope(|s| {
+            s.spawn(|| {
+                events[2].wait_for_listeners(1);
+                assert!(events[2].notify_one());
+            });
+            assert_eq!(set.wait_any(), (2, State::Notified));
+        });
+        assert!(events.iter().all(|event| event.listener_count() == 0));
+        // the registration is gone, nobody is left to wake
+        assert!(!events[0].notify_one());
+    }
+
+    #[test]
+    fn completes_on_permit_or_close() {
+        let events = [Event::default(), Event::default()];
+        events[1].notify_additional(1);
+        assert_eq!(EventSet::new(&events).wait_any(), (1, State::Notified));
+        events[0].close();
+        assert_eq!(EventSet::new(&events).wait_any(), (0, State::Closed));
+    }
+
+    #[test]
+    fn wait_all() {
+        let events = [Event::default(), Event::default()];
+        let set = EventSet::new(&events);
+        // notifications before registering don't count
+        events[0].notify_all();
+        thread::scope(|s| {
+            s.spawn(|| {
+                for event in events.iter().rev() {
+                    event.wait_for_listeners(1);
+                    event.notify_all();
+                }
+            });
+            assert_eq!(set.wait_all(), [State::Notified, State::Notified]);
+        });
+    }
+
+    #[tokio::test]
+    async fn notified_all() {
+        let first = Event::new_shared();
+        let second = Event::new_shared();
+        let notifiers = [first.clone(), second.clone()];
+        let jh = tokio::spawn(async move {
+            for notifier in &notifiers {
+                notifier.wait_for_listeners_async(1).await;
+            }
+            notifiers[1].notify_all();
+            notifiers[0].close();
+        });
+        let set = EventSet::new([&*first, &*second]);
+        assert_eq!(set.notified_all().await, [State::Closed, State::Notified]);
+        jh.await.expect("notifier panicked");
+    }
+
+    #[tokio::test]
+    async fn notified_any() {
+        let first = Event::new_shared();
+        let second = Event::new_shared();
+        let notifier = first.clone();
+        let jh = tokio::spawn(async move {
+            notifier.wait_for_listeners_async(1).await;
+            notifier.notify_one();
+        });
+        let set = EventSet::new([&*first, &*second]);
+        assert_eq!(set.notified_any().await, (0, State::Notified));
+        jh.await.expect("notifier panicked");
+        assert_eq!(second.listener_count(), 0);
+    }
+}
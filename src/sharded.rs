@@ -0,0 +1,122 @@
+use crate::{Event, EventBuilder, Listener, WaitGuard};
+use portable_atomic::{AtomicUsize, Ordering};
+
+/// An event split over several independent shards so that hundreds of threads listening and
+/// notifying don't all contend on one chain lock. Built with [`EventBuilder::build_sharded`].
+///
+/// Each thread listens on its own shard (threads are spread over the shards round robin) and
+/// [`ShardedEvent::notify_one`] visits the shards round robin, so wake order is only FIFO within
+/// a shard.
+#[derive(Debug)]
+pub struct ShardedEvent {
+    shards: Box<[Event]>,
+    next_notify: AtomicUsize,
+}
+
+thread_local! {
+    static SHARD: usize = NEXT_SHARD.fetch_add(1, Ordering::Relaxed);
+}
+
+static NEXT_SHARD: AtomicUsize = AtomicUsize::new(0);
+
+impl EventBuilder {
+    /// Builds a [`ShardedEvent`] with `shards` shards (at least one), each configured like
+    /// [`EventBuilder::build`]. A bounded capacity applies to each shard.
+    pub fn build_sharded(self, shards: usize) -> ShardedEvent {
+        let shards = (0..shards.max(1)).map(|_| self.clone().build()).collect();
+        ShardedEvent {
+            shards,
+            next_notify: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl ShardedEvent {
+    pub fn new(shards: usize) -> Self {
+        Event::builder().build_sharded(shards)
+    }
+
+    /// The current thread's shard.
+    fn shard(&self) -> &Event {
+        let index = SHARD.try_with(|shard| *shard).unwrap_or(0);
+        &self.shards[index % self.shards.len()]
+    }
+
+    pub fn listen(&self) -> WaitGuard {
+        self.shard().listen()
+    }
+
+    pub fn listen_async(&self, waker: core::task::Waker) -> WaitGuard {
+        self.shard().listen_async(waker)
+    }
+
+    pub fn notified(&self) -> Listener<'_> {
+        self.shard().notified()
+    }
+
+    /// Wakes one live listener, starting from the next shard in turn and moving on while a shard
+    /// has nobody to wake.
+    pub fn notify_one(&self) -> bool {
+        let start = self.next_notify.fetch_add(1, Ordering::Relaxed);
+        (0..self.shards.len())
+            .any(|offset| self.shards[(start + offset) % self.shards.len()].notify_one())
+    }
+
+    /// Wakes every listener on every shard, returning how many were still waiting.
+    pub fn notify_all(&self) -> usize {
+        self.shards.iter().map(Event::notify_all).sum()
+    }
+
+    pub fn close(&self) {
+        self.shards.iter().for_each(Event::close);
+    }
+
+    pub fn listener_count(&self) -> usize {
+        self.shards.iter().map(Event::listener_count).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::State;
+    use std::thread;
+
+    #[test]
+    fn notify_one_finds_listener_on_any_shard() {
+        let event = &ShardedEvent::new(4);
+        thread::scope(|s| {
+            let workers: Vec<_> = (0..4)
+                .map(|_| {
+                    s.spawn(move || {
+                        let guard = event.listen();
+                        guard.wait();
+                        guard.get_state()
+                    })
+                })
+                .collect();
+            while event.listener_count() < 4 {
+                thread::yield_now();
+            }
+            for _ in 0..4 {
+                assert!(event.notify_one());
+            }
+            assert!(!event.notify_one());
+            for worker in workers {
+                assert_eq!(worker.join().expect("couldn't join!"), State::Notified);
+            }
+        });
+    }
+
+    #[test]
+    fn notify_all_and_close() {
+        let event = Event::builder().build_sharded(3);
+        let guards: Vec<_> = (0..3).map(|_| event.listen()).collect();
+        assert_eq!(event.notify_all(), 3);
+        assert!(guards.iter().all(|g| g.get_state() == State::Notified));
+        let guard = event.listen();
+        event.close();
+        assert_eq!(guard.get_state(), State::Closed);
+        assert_eq!(event.listen().get_state(), State::Closed);
+    }
+}
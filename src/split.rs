@@ -0,0 +1,98 @@
+use crate::{Event, Listener, RecvError, SharedEvent, WaitGuard};
+use std::sync::Arc;
+
+/// The notifying half of an [`Event`] from [`Event::split`]. The event is closed once every clone
+/// of the notifier has been dropped.
+#[derive(Debug, Clone)]
+pub struct Notifier {
+    inner: Arc<CloseOnDrop>,
+}
+
+/// The listening half of an [`Event`] from [`Event::split`].
+#[derive(Debug, Clone)]
+pub struct Subscriber {
+    event: SharedEvent,
+}
+
+#[derive(Debug)]
+struct CloseOnDrop(SharedEvent);
+
+impl Drop for CloseOnDrop {
+    fn drop(&mut self) {
+        self.0.close();
+    }
+}
+
+impl Event {
+    /// Splits the event into a handle that can only notify and one that can only listen.
+    pub fn split(self) -> (Notifier, Subscriber) {
+        let event = Arc::new(self);
+        let notifier = Notifier {
+            inner: Arc::new(CloseOnDrop(event.clone())),
+        };
+        (notifier, Subscriber { event })
+    }
+}
+
+impl Notifier {
+    pub fn notify_one(&self) -> bool {
+        self.inner.0.notify_one()
+    }
+
+    pub fn notify_all(&self) -> usize {
+        self.inner.0.notify_all()
+    }
+}
+
+impl Subscriber {
+    /// Listens on the event, once every notifier is gone the guard is already
+    /// [`State::Closed`](crate::State::Closed).
+    pub fn listen(&self) -> WaitGuard {
+        self.event.listen()
+    }
+
+    pub fn notified(&self) -> Listener<'_> {
+        self.event.notified()
+    }
+
+    pub async fn recv(&self) -> Result<(), RecvError> {
+        self.event.recv().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::State;
+    use std::time::Duration;
+
+    #[test]
+    fn notify_through_notifier() {
+        let (notifier, subscriber) = Event::default().split();
+        let other = subscriber.clone();
+        std::thread::scope(|s| {
+            let jh = s.spawn(|| {
+                let guard = other.listen();
+                guard.wait();
+                guard.get_state()
+            });
+            while subscriber.event.chain().is_empty() {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            notifier.notify_one();
+            assert_eq!(jh.join().expect("couldn't join!"), State::Notified);
+        });
+    }
+
+    #[test]
+    fn dropping_notifiers_closes() {
+        let (notifier, subscriber) = Event::default().split();
+        let second = notifier.clone();
+        let guard = subscriber.listen();
+        drop(notifier);
+        assert_eq!(guard.get_state(), State::Waiting);
+        drop(second);
+        assert_eq!(guard.get_state(), State::Closed);
+        assert_eq!(subscriber.listen().get_state(), State::Closed);
+    }
+}
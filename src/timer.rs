@@ -0,0 +1,238 @@
+use crate::Event;
+use core::cmp::Reverse;
+use portable_atomic::AtomicBool;
+use std::collections::BinaryHeap;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError, Weak};
+use std::time::{Duration, Instant};
+
+/// A notification armed with [`Event::notify_at`] or [`Event::notify_after`]. Dropping the handle
+/// leaves the notification armed, use [`ScheduledNotify::cancel`] to disarm it.
+#[derive(Debug)]
+pub struct ScheduledNotify {
+    done: Arc<AtomicBool>,
+}
+
+impl ScheduledNotify {
+    /// Disarms the notification, returning false if it already fired (or was already cancelled).
+    /// Cancelled entries are swept out of the timer once they make up half of it, so arming and
+    /// cancelling long timeouts in a loop doesn't grow it.
+    pub fn cancel(&self) -> bool {
+        Timer::get().cancel(&self.done)
+    }
+
+    /// Whether the notification is still armed.
+    pub fn is_pending(&self) -> bool {
+        !self.done.load(Ordering::Acquire)
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    at: Instant,
+    /// Breaks ties between entries due at the same instant, keeping them in scheduling order.
+    id: u64,
+    event: Weak<Event>,
+    done: Arc<AtomicBool>,
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        (self.at, self.id) == (other.at, other.id)
+    }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        (self.at, self.id).cmp(&(other.at, other.id))
+    }
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    next_id: u64,
+    heap: BinaryHeap<Reverse<Entry>>,
+    /// Roughly how many entries in the heap have been cancelled, see [`Timer::cancel`].
+    cancelled: usize,
+}
+
+/// The shared timer thread's queue, started by the first scheduled notification.
+#[derive(Debug, Default)]
+struct Timer {
+    entries: Mutex<Entries>,
+    /// Notified when an entry is scheduled so the thread can pick up an earlier deadline.
+    scheduled: Event,
+}
+
+impl Timer {
+    fn get() -> &'static Timer {
+        static TIMER: OnceLock<Timer> = OnceLock::new();
+        static STARTED: OnceLock<()> = OnceLock::new();
+        let timer = TIMER.get_or_init(Timer::default);
+        STARTED.get_or_init(|| {
+            std::thread::Builder::new()
+                .name("wake-me-timer".into())
+                .spawn(move || timer.run())
+                .expect("failed to spawn the timer thread");
+        });
+        timer
+    }
+
+    fn entries(&self) -> MutexGuard<'_, Entries> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn schedule(&self, event: &Arc<Event>, at: Instant) -> ScheduledNotify {
+        let done = Arc::new(AtomicBool::new(false));
+        let mut entries = self.entries();
+        let id = entries.next_id;
+        entries.heap.push(Reverse(Entry {
+            at,
+            id,
+            event: Arc::downgrade(event),
+            done: done.clone(),
+        }));
+        entries.next_id += 1;
+        drop(entries);
+        self.scheduled.notify_one();
+        ScheduledNotify { done }
+    }
+
+    fn cancel(&self, done: &AtomicBool) -> bool {
+        let mut entries = self.entries();
+        if done.swap(true, Ordering::AcqRel) {
+            return false;
+        }
+        // an entry popped by the thread but not fired yet is counted too, which only brings the
+        // sweep forward
+        entries.cancelled += 1;
+        if entries.cancelled > entries.heap.len() / 2 {
+            entries
+                .heap
+                .retain(|Reverse(entry)| !entry.done.load(Ordering::Acquire));
+            entries.cancelled = 0;
+        }
+        true
+    }
+
+    fn run(&self) {
+        loop {
+            // listen before reading the queue so an entry scheduled in between wakes us
+            let guard = self.scheduled.listen();
+            let now = Instant::now();
+            let mut due = Vec::new();
+            let next = {
+                let mut entries = self.entries();
+                while let Some(Reverse(entry)) = entries.heap.peek() {
+                    if entry.at > now {
+                        break;
+                    }
+                    if entry.done.load(Ordering::Acquire) {
+                        entries.cancelled = entries.cancelled.saturating_sub(1);
+                    }
+                    due.extend(entries.heap.pop());
+                }
+                entries.heap.peek().map(|Reverse(entry)| entry.at)
+            };
+            for Reverse(entry) in due {
+                if entry.done.swap(true, Ordering::AcqRel) {
+                    continue;
+                }
+                if let Some(event) = entry.event.upgrade() {
+                    event.notify_all();
+                }
+            }
+            // a timeout just means the next entry is due
+            let _ = guard.wait_opt_deadline(next);
+        }
+    }
+}
+
+impl Event {
+    /// Calls [`Event::notify_all`] at `at` from a shared timer thread, for timeouts and periodic
+    /// pokes without pulling in a timer crate. The timer only holds on to the event weakly, nothing
+    /// fires once it has been dropped.
+    ///
+    /// ```
+    /// # use wake_me::{Event, State};
+    /// # use std::time::{Duration, Instant};
+    /// let event = Event::new_shared();
+    /// let guard = event.listen();
+    /// event.notify_at(Instant::now() + Duration::from_millis(5));
+    /// guard.wait();
+    /// assert_eq!(guard.get_state(), State::Notified);
+    /// ```
+    pub fn notify_at(self: &Arc<Self>, at: Instant) -> ScheduledNotify {
+        Timer::get().schedule(self, at)
+    }
+
+    /// [`Event::notify_at`] `delay` from now.
+    pub fn notify_after(self: &Arc<Self>, delay: Duration) -> ScheduledNotify {
+        self.notify_at(Instant::now() + delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::State;
+
+    #[test]
+    fn fires_in_order() {
+        let first = Event::new_shared();
+        let second = Event::new_shared();
+        let (a, b) = (first.listen(), second.listen());
+        let late = second.notify_after(Duration::from_millis(40));
+        let early = first.notify_after(Duration::from_millis(5));
+        a.wait();
+        assert_eq!(b.get_state(), State::Waiting);
+        assert!(!early.is_pending());
+        assert!(late.is_pending());
+        b.wait();
+        assert!(!late.cancel());
+    }
+
+    #[test]
+    fn cancelled_never_fires() {
+        let event = Event::new_shared();
+        let guard = event.listen();
+        let scheduled = event.notify_after(Duration::from_millis(5));
+        assert!(scheduled.cancel());
+        assert!(!scheduled.cancel());
+        let deadline = Instant::now() + Duration::from_millis(30);
+        assert!(guard.wait_deadline(deadline).is_err());
+    }
+
+    #[test]
+    fn cancelled_entries_are_swept() {
+        let event = Event::new_shared();
+        for _ in 0..1000 {
+            let scheduled = event.notify_after(Duration::from_secs(3600));
+            assert!(scheduled.cancel());
+        }
+        // other tests share the timer, but only ever have a few entries armed
+        assert!(Timer::get().entries().heap.len() < 100);
+        // the swept entries let go of the event too
+        assert!(Arc::weak_count(&event) < 100);
+    }
+
+    #[test]
+    fn dropped_event_is_skipped() {
+        let event = Event::new_shared();
+        let scheduled = event.notify_after(Duration::from_millis(5));
+        drop(event);
+        let event = Event::new_shared();
+        let guard = event.listen();
+        event.notify_after(Duration::from_millis(10));
+        guard.wait();
+        assert!(!scheduled.is_pending());
+    }
+}
@@ -1,6 +1,12 @@
-use portable_atomic::AtomicU8;
+use crate::chain::Chain;
+use crate::park;
+use atomic_waker::AtomicWaker;
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+use portable_atomic::{AtomicBool, AtomicU64, AtomicU8, AtomicUsize};
 use std::sync::atomic::Ordering;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 use std::time::Instant;
 
 #[repr(u8)]
@@ -9,37 +15,136 @@ pub enum State {
     Waiting = 0,
     Notified = 1,
     Dropped = 2,
+    /// The event was closed, see [`Event::close`](crate::Event::close).
+    Closed = 3,
+    /// The listener was never registered, the event is bounded and was already full, see
+    /// [`EventBuilder::bounded`](crate::EventBuilder::bounded).
+    Full = 4,
 }
 
+/// Set alongside the state once the guard has finished waiting (or was dropped).
+pub(crate) const ACKNOWLEDGED: u8 = 0x80;
+
 impl From<u8> for State {
     fn from(value: u8) -> Self {
-        match value {
+        match value & !ACKNOWLEDGED {
             0 => State::Waiting,
             1 => State::Notified,
             2 => State::Dropped,
+            3 => State::Closed,
+            4 => State::Full,
             _ => panic!("unknown state"),
         }
     }
 }
 
-#[derive(Debug)]
 enum InnerWaker {
     Sync(std::thread::Thread),
     Async(core::task::Waker),
+    Custom(Box<dyn Fn() + Send + Sync>),
+}
+
+impl core::fmt::Debug for InnerWaker {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            InnerWaker::Sync(thread) => f.debug_tuple("Sync").field(thread).finish(),
+            InnerWaker::Async(waker) => f.debug_tuple("Async").field(waker).finish(),
+            InnerWaker::Custom(_) => write!(f, "Custom"),
+        }
+    }
 }
 
 impl InnerWaker {
-    fn wake_by_ref(&self) {
+    fn wake_by_ref(&self, key: usize) {
         match self {
-            InnerWaker::Sync(thread) => thread.unpark(),
+            InnerWaker::Sync(thread) => park::unpark(thread, key),
             InnerWaker::Async(waker) => waker.wake_by_ref(),
+            InnerWaker::Custom(f) => f(),
         }
     }
+}
+
+/// How a listener gets woken.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum WakerKind {
+    /// A parked thread.
+    Sync,
+    /// An async task.
+    Async,
+    /// A user supplied callback, e.g. from `Event::listen_fd`.
+    Custom,
+}
+
+/// Describes the listener woken by [`Event::notify_one_detailed`](crate::Event::notify_one_detailed).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct WakeInfo {
+    /// See [`WaitGuard::id`].
+    pub id: usize,
+    pub kind: WakerKind,
+    /// Time between `listen` and the wake.
+    #[cfg(feature = "latency")]
+    pub latency: Duration,
+}
 
-    fn wake(self) {
+/// One queued listener in an [`Event::debug_snapshot`](crate::Event::debug_snapshot).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ListenerSnapshot {
+    /// See [`WaitGuard::id`].
+    pub id: usize,
+    pub kind: WakerKind,
+    pub state: State,
+    /// Time since the listener registered.
+    #[cfg(feature = "latency")]
+    pub age: Duration,
+}
+
+/// What a [`WaitGuard`] shares with its [`Waker`]s.
+#[derive(Debug, Default)]
+pub(crate) struct ListenerState {
+    state: AtomicU8,
+    /// The task awaiting the guard, woken along with the listener, see the `Future` impl.
+    task: AtomicWaker,
+    /// Set once a task has been registered, so wakers of guards nobody awaits skip `task`.
+    awaited: AtomicBool,
+    /// The chain the guard is registered with, which it unlinks itself from if dropped while
+    /// still waiting. Kept here so a state reused from the pool already has it.
+    chain: OnceLock<Arc<Chain>>,
+}
+
+impl ListenerState {
+    /// Wakes the task awaiting the guard, if there is one.
+    fn wake_task(&self) {
+        // SeqCst pairs with the store in register_task and the claim of the state, either the
+        // waker sees the task or the poll sees the guard isn't waiting any more
+        if self.awaited.load(Ordering::SeqCst) {
+            self.task.wake();
+        }
+    }
+}
+
+impl core::ops::Deref for ListenerState {
+    type Target = AtomicU8;
+
+    fn deref(&self) -> &AtomicU8 {
+        &self.state
+    }
+}
+
+#[derive(Debug)]
+enum Slot {
+    Shared(Arc<ListenerState>),
+    /// State owned by a pinned [`ListenerNode`](crate::ListenerNode) which unlinks itself from
+    /// the chain before it goes away.
+    Pinned(NonNull<AtomicU8>),
+}
+
+impl Slot {
+    fn get(&self) -> &AtomicU8 {
         match self {
-            InnerWaker::Sync(thread) => thread.unpark(),
-            InnerWaker::Async(waker) => waker.wake(),
+            Slot::Shared(state) => state,
+            // SAFETY: a pinned node outlives its waker's place in the chain and the waker is only
+            // used with the chain locked until it's claimed
+            Slot::Pinned(state) => unsafe { state.as_ref() },
         }
     }
 }
@@ -47,123 +152,905 @@ impl InnerWaker {
 #[derive(Debug)]
 pub struct Waker {
     inner: InnerWaker,
-    state: Arc<AtomicU8>,
+    /// Taken once a pinned waker has been claimed so its slot is never touched afterwards.
+    slot: Option<Slot>,
+    claimed: bool,
+    key: usize,
+    affinity: Option<u16>,
+    seq: Option<u64>,
+    tag: Option<u64>,
+    priority: u8,
+    /// Where to record `index` if this waker's claim succeeds, for listeners registered with more
+    /// than one event.
+    report: Option<(Arc<AtomicUsize>, usize)>,
+    #[cfg(feature = "latency")]
+    registered: Instant,
 }
 
+// SAFETY: the only non thread safe part is a pinned slot which points at an atomic that is only
+// accessed while the chain is locked
+unsafe impl Send for Waker {}
+unsafe impl Sync for Waker {}
+
 impl Drop for Waker {
     fn drop(&mut self) {
-        let _ = self.state.compare_exchange(
+        // a claimed guard may already have been re-armed, it isn't ours to cancel any more
+        if self.claimed {
+            return;
+        }
+        let Some(slot) = self.slot.take() else {
+            return;
+        };
+        let state = slot.get().compare_exchange(
             State::Waiting as u8,
             State::Dropped as u8,
             Ordering::SeqCst,
             Ordering::Relaxed,
         );
-        self.inner.wake_by_ref();
+        // only a listener that is still waiting needs to find out that it won't be notified
+        if state.is_ok() {
+            self.inner.wake_by_ref(self.key);
+            if let Slot::Shared(state) = slot {
+                state.wake_task();
+            }
+        }
     }
 }
 
 impl Waker {
     pub fn new() -> (Self, WaitGuard) {
-        let waker = Self {
-            inner: InnerWaker::Sync(std::thread::current()),
-            state: Arc::new(AtomicU8::new(State::Waiting as u8)),
-        };
-        let sleeper = waker.guard();
-        (waker, sleeper)
+        Self::with_inner(InnerWaker::Sync(current_thread()))
     }
 
     pub fn new_async(waker: core::task::Waker) -> (Self, WaitGuard) {
-        let waker = Self {
-            inner: InnerWaker::Async(waker),
-            state: Arc::new(AtomicU8::new(State::Waiting as u8)),
+        Self::with_inner(InnerWaker::Async(waker))
+    }
+
+    /// Runs `f` instead of unparking a thread or waking a task. `f` is called outside the chain
+    /// lock but shouldn't block.
+    pub fn new_custom(f: impl Fn() + Send + Sync + 'static) -> (Self, WaitGuard) {
+        Self::with_inner(InnerWaker::Custom(Box::new(f)))
+    }
+
+    /// Creates a sync waker for state owned by the caller.
+    ///
+    /// # Safety
+    /// `state` must stay valid for as long as the waker is in the chain, and the waker must only be
+    /// used with the chain locked until it has been claimed.
+    pub unsafe fn new_pinned(state: &AtomicU8) -> Self {
+        Self::from_slot(
+            InnerWaker::Sync(current_thread()),
+            Slot::Pinned(NonNull::from(state)),
+        )
+    }
+
+    /// Async version of [`Waker::new_pinned`].
+    ///
+    /// # Safety
+    /// As for [`Waker::new_pinned`].
+    pub unsafe fn new_pinned_async(state: &AtomicU8, waker: core::task::Waker) -> Self {
+        Self::from_slot(InnerWaker::Async(waker), Slot::Pinned(NonNull::from(state)))
+    }
+
+    /// Creates a sync waker for an existing guard, see [`WaitGuard::try_rearm`].
+    pub fn for_guard(guard: &WaitGuard) -> Self {
+        Self::from_slot(
+            InnerWaker::Sync(current_thread()),
+            Slot::Shared(guard.state.clone()),
+        )
+    }
+
+    /// Async version of [`Waker::for_guard`].
+    pub fn for_guard_async(guard: &WaitGuard, waker: core::task::Waker) -> Self {
+        Self::from_slot(InnerWaker::Async(waker), Slot::Shared(guard.state.clone()))
+    }
+
+    /// [`Waker::new`] reusing `state` if given rather than allocating. Nothing else may be using
+    /// `state`.
+    pub fn new_reusing(state: Option<Arc<ListenerState>>) -> (Self, WaitGuard) {
+        Self::with_state(InnerWaker::Sync(current_thread()), state)
+    }
+
+    /// [`Waker::new_async`] reusing `state`, see [`Waker::new_reusing`].
+    pub fn new_async_reusing(
+        state: Option<Arc<ListenerState>>,
+        waker: core::task::Waker,
+    ) -> (Self, WaitGuard) {
+        Self::with_state(InnerWaker::Async(waker), state)
+    }
+
+    fn with_inner(inner: InnerWaker) -> (Self, WaitGuard) {
+        Self::with_state(inner, None)
+    }
+
+    fn with_state(inner: InnerWaker, state: Option<Arc<ListenerState>>) -> (Self, WaitGuard) {
+        let state = match state {
+            Some(state) => {
+                state.store(State::Waiting as u8, Ordering::Relaxed);
+                state.awaited.store(false, Ordering::Relaxed);
+                state
+            }
+            None => Arc::default(),
         };
-        let sleeper = waker.guard();
-        (waker, sleeper)
+        let sleeper = WaitGuard::new(state.clone());
+        (Self::from_slot(inner, Slot::Shared(state)), sleeper)
+    }
+
+    fn from_slot(inner: InnerWaker, slot: Slot) -> Self {
+        Self {
+            inner,
+            key: key(slot.get()),
+            slot: Some(slot),
+            claimed: false,
+            affinity: None,
+            seq: None,
+            tag: None,
+            priority: 0,
+            report: None,
+            #[cfg(feature = "latency")]
+            registered: Instant::now(),
+        }
     }
 
-    pub fn wake(&self) -> bool {
-        let state = self.state.compare_exchange(
+    pub fn kind(&self) -> WakerKind {
+        match self.inner {
+            InnerWaker::Sync(_) => WakerKind::Sync,
+            InnerWaker::Async(_) => WakerKind::Async,
+            InnerWaker::Custom(_) => WakerKind::Custom,
+        }
+    }
+
+    pub fn info(&self) -> WakeInfo {
+        WakeInfo {
+            id: self.key,
+            kind: self.kind(),
+            #[cfg(feature = "latency")]
+            latency: self.elapsed(),
+        }
+    }
+
+    pub fn snapshot(&self) -> ListenerSnapshot {
+        ListenerSnapshot {
+            id: self.key,
+            kind: self.kind(),
+            state: self.state(),
+            #[cfg(feature = "latency")]
+            age: self.elapsed(),
+        }
+    }
+
+    /// Time since this waker was registered.
+    #[cfg(feature = "latency")]
+    pub fn elapsed(&self) -> Duration {
+        self.registered.elapsed()
+    }
+
+    /// Returns false without waking anyone if the guard was dropped. A dropped slot never consumes
+    /// a notification, callers are expected to move on to the next waker in the chain.
+    pub fn wake(&mut self) -> bool {
+        if !self.claim() {
+            return false;
+        }
+        self.unpark();
+        true
+    }
+
+    /// Moves the listener to notified without waking it, returning false if it was dropped. A
+    /// pinned slot is released either way so this must happen with the chain locked, the wake can
+    /// be done later with [`Waker::unpark`].
+    pub fn claim(&mut self) -> bool {
+        self.claim_as(State::Notified)
+    }
+
+    /// [`Waker::claim`] finishing the listener in `state` rather than [`State::Notified`].
+    pub fn claim_as(&mut self, state: State) -> bool {
+        self.claim_with(state, Ordering::SeqCst)
+    }
+
+    /// [`Waker::claim_as`] with the given ordering on a successful claim.
+    pub fn claim_with(&mut self, state: State, success: Ordering) -> bool {
+        if self.claimed {
+            return true;
+        }
+        let Some(slot) = &self.slot else {
+            return false;
+        };
+        let state = slot.get().compare_exchange(
             State::Waiting as u8,
-            State::Notified as u8,
-            Ordering::SeqCst,
+            state as u8,
+            success,
             Ordering::Relaxed,
         );
-        if state.is_ok() {
-            self.inner.wake_by_ref();
-            return true;
+        self.claimed = state.is_ok();
+        if let (true, Some((winner, index))) = (self.claimed, &self.report) {
+            winner.store(*index, Ordering::Release);
         }
-        debug_assert_eq!(state.unwrap_err(), State::Dropped as u8);
-        false
+        if matches!(slot, Slot::Pinned(_)) {
+            self.slot = None;
+        }
+        self.claimed
+    }
+
+    /// The shared listener state, `None` for pinned listeners.
+    pub fn shared_state(&self) -> Option<Arc<ListenerState>> {
+        match &self.slot {
+            Some(Slot::Shared(state)) => Some(state.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn is_claimed(&self) -> bool {
+        self.claimed
+    }
+
+    /// Wakes the thread or task behind a claimed waker.
+    pub fn unpark(&self) {
+        debug_assert!(self.claimed);
+        self.inner.wake_by_ref(self.key);
+        if let Some(Slot::Shared(state)) = &self.slot {
+            state.wake_task();
+        }
+    }
+
+    pub fn state(&self) -> State {
+        match &self.slot {
+            Some(slot) => slot.get().load(Ordering::Acquire).into(),
+            None if self.claimed => State::Notified,
+            None => State::Dropped,
+        }
+    }
+
+    /// Identifies the listener state this waker belongs to.
+    pub fn key(&self) -> usize {
+        self.key
+    }
+
+    /// Marks the listener notified without waking it, for listeners that haven't started waiting.
+    pub fn complete(mut self) {
+        self.claim();
+    }
+
+    pub fn set_affinity(&mut self, node: u16) {
+        self.affinity = Some(node);
+    }
+
+    pub fn affinity(&self) -> Option<u16> {
+        self.affinity
+    }
+
+    /// The sequence the listener has already seen, see [`Event::listen_at`](crate::Event::listen_at).
+    pub fn set_seq(&mut self, seq: u64) {
+        self.seq = Some(seq);
+    }
+
+    pub fn seq(&self) -> Option<u64> {
+        self.seq
+    }
+
+    /// The caller's category for the listener, see [`Event::listen_tagged`](crate::Event::listen_tagged).
+    pub fn set_tag(&mut self, tag: u64) {
+        self.tag = Some(tag);
+    }
+
+    pub fn tag(&self) -> Option<u64> {
+        self.tag
+    }
+
+    /// See [`Event::listen_with_priority`](crate::Event::listen_with_priority).
+    pub fn set_priority(&mut self, priority: u8) {
+        self.priority = priority;
+    }
+
+    pub fn priority(&self) -> u8 {
+        self.priority
+    }
+
+    /// Has a successful claim store `index` in `winner`, see [`EventSet`](crate::EventSet).
+    pub fn report_claim(&mut self, winner: Arc<AtomicUsize>, index: usize) {
+        self.report = Some((winner, index));
     }
 
     fn reset(&self) {
-        self.state.store(State::Waiting as u8, Ordering::SeqCst);
+        if let Some(slot) = &self.slot {
+            slot.get().store(State::Waiting as u8, Ordering::SeqCst);
+        }
+    }
+    /// Wakes `waker` instead from now on, unless it already would.
+    pub fn set_async(&mut self, waker: &core::task::Waker) {
+        if !matches!(&self.inner, InnerWaker::Async(current) if current.will_wake(waker)) {
+            self.inner = InnerWaker::Async(waker.clone());
+        }
     }
+
     fn reset_async(&mut self, waker: core::task::Waker) {
-        self.state.store(State::Waiting as u8, Ordering::SeqCst);
+        self.reset();
         self.inner = InnerWaker::Async(waker);
     }
-    fn guard(&self) -> WaitGuard {
-        WaitGuard::new(self.state.clone())
+}
+
+thread_local! {
+    static CURRENT: std::thread::Thread = std::thread::current();
+}
+
+/// The current thread's handle, cached so hot listen loops don't look it up every time.
+fn current_thread() -> std::thread::Thread {
+    // the cache is gone once the thread starts tearing down its locals
+    CURRENT
+        .try_with(Clone::clone)
+        .unwrap_or_else(|_| std::thread::current())
+}
+
+pub(crate) fn key(state: &AtomicU8) -> usize {
+    state as *const AtomicU8 as usize
+}
+
+const MAX_DEADLINE_SPINS: u32 = 64;
+
+/// Marks that the guard is done waiting, see [`Event::notify_all_and_wait`](crate::Event::notify_all_and_wait).
+pub(crate) fn acknowledge(state: &AtomicU8) {
+    // the guard acknowledges on every wait and again when dropped, only the first has to write
+    if state.load(Ordering::Relaxed) & ACKNOWLEDGED == 0 {
+        state.fetch_or(ACKNOWLEDGED, Ordering::Release);
+    }
+}
+
+pub(crate) fn is_acknowledged(state: &AtomicU8) -> bool {
+    state.load(Ordering::Acquire) & ACKNOWLEDGED != 0
+}
+
+pub(crate) fn get_state(state: &AtomicU8) -> State {
+    state.load(Ordering::Acquire).into()
+}
+
+/// Parks the current thread until `state` leaves [`State::Waiting`].
+pub(crate) fn wait(state: &AtomicU8) {
+    loop {
+        match get_state(state) {
+            State::Waiting => {
+                park::park(state);
+            }
+            _ => return,
+        }
+    }
+}
+
+/// Returns the time left until `deadline` once `state` leaves [`State::Waiting`].
+pub(crate) fn wait_deadline(state: &AtomicU8, deadline: Instant) -> Result<Duration, WaitError> {
+    let mut spins = 1;
+    let mut max_park_duration = deadline.saturating_duration_since(Instant::now());
+    while !max_park_duration.is_zero() {
+        match get_state(state) {
+            State::Waiting => {
+                park::park_timeout(state, max_park_duration);
+                // park can return spuriously, spin for a little while (backing off) before
+                // reading the clock and parking again so unpark storms don't burn the CPU
+                for _ in 0..spins {
+                    if get_state(state) != State::Waiting {
+                        return Ok(deadline.saturating_duration_since(Instant::now()));
+                    }
+                    core::hint::spin_loop();
+                }
+                spins = (spins * 2).min(MAX_DEADLINE_SPINS);
+                max_park_duration = deadline.saturating_duration_since(Instant::now());
+            }
+            _ => return Ok(max_park_duration),
+        }
+    }
+    // a notification that beat the final check wins over the timeout
+    match get_state(state) {
+        State::Waiting => Err(WaitError::Timeout),
+        _ => Ok(Duration::ZERO),
+    }
+}
+
+const MIN_BACKOFF_PARK: Duration = Duration::from_micros(50);
+
+/// Like [`wait_deadline`] but parks for short, doubling, timeouts rather than the whole remaining
+/// time so threads sharing a deadline don't all re-check at once after correlated unparks.
+pub(crate) fn wait_deadline_backoff(state: &AtomicU8, deadline: Instant) -> Result<(), WaitError> {
+    let mut park_duration = MIN_BACKOFF_PARK;
+    loop {
+        if get_state(state) != State::Waiting {
+            return Ok(());
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(WaitError::Timeout);
+        }
+        park::park_timeout(state, park_duration.min(remaining));
+        park_duration = park_duration.saturating_mul(2);
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WaitError {
     Timeout,
+    /// Not every woken listener acknowledged before the timeout, `acked` of them did.
+    AckTimeout {
+        acked: usize,
+    },
+    /// The event was closed, see [`Event::close`](crate::Event::close).
+    Closed,
+    /// The guard is still waiting, see [`WaitGuard::try_wait`].
+    WouldBlock,
+    /// The listener was never registered, see [`State::Full`].
+    Full,
 }
 
 impl core::fmt::Display for WaitError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             WaitError::Timeout => write!(f, "timeout"),
+            WaitError::AckTimeout { acked } => {
+                write!(f, "timeout with only {acked} listeners acknowledged")
+            }
+            WaitError::Closed => write!(f, "event closed"),
+            WaitError::WouldBlock => write!(f, "still waiting"),
+            WaitError::Full => write!(f, "event full"),
+        }
+    }
+}
+
+/// What a wait with a deadline saw when it stopped waiting, see [`WaitGuard::wait_deadline`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Waited {
+    /// The state the guard finished in, never [`State::Waiting`].
+    pub state: State,
+    /// How long was left until the deadline, for spreading one budget over several waits.
+    pub remaining: Duration,
+}
+
+/// How [`WaitGuard::wait_timeout`] finished.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitStatus {
+    Notified,
+    /// The event was closed, see [`Event::close`](crate::Event::close).
+    Closed,
+    /// Nothing happened before the timeout, the guard is still registered and can keep waiting.
+    TimedOut,
+    /// The waker was dropped without notifying, e.g. along with its event.
+    Dropped,
+    /// The listener was never registered, see [`State::Full`].
+    Full,
+}
+
+/// Why [`Event::try_listen`](crate::Event::try_listen) couldn't register.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListenError {
+    /// The event has been closed.
+    Closed,
+    /// The event is bounded and already has as many listeners as it allows.
+    Full,
+}
+
+impl core::fmt::Display for ListenError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ListenError::Closed => write!(f, "event closed"),
+            ListenError::Full => write!(f, "event full"),
         }
     }
 }
 
 #[derive(Debug)]
 pub struct WaitGuard {
-    state: Arc<AtomicU8>,
+    state: Arc<ListenerState>,
+    ticket: AtomicU64,
+    spins: u32,
+    /// Set once the guard has been polled, its task is let go of when it is dropped.
+    awaited: bool,
+    /// Set for a guard from [`Event::listen_with_key`](crate::Event::listen_with_key), whose waker
+    /// is indexed under this key instead of queued in the chain.
+    key: Option<u64>,
 }
 
 impl Drop for WaitGuard {
     fn drop(&mut self) {
-        self.state.store(State::Dropped as u8, Ordering::Release);
+        // only a guard that is still waiting gives up its slot, a completed notification stays
+        // visible. Nothing moves a guard back to waiting behind its back, a guard seen finished
+        // doesn't need the compare exchange
+        let cancelled = get_state(&self.state) == State::Waiting
+            && self
+                .state
+                .compare_exchange(
+                    State::Waiting as u8,
+                    State::Dropped as u8,
+                    Ordering::Release,
+                    Ordering::Relaxed,
+                )
+                .is_ok();
+        acknowledge(&self.state);
+        // a claimed waker is taken out of the chain in the same critical section as the claim,
+        // only a cancelled one can still be in there
+        if let (true, Some(chain)) = (cancelled, self.state.chain.get()) {
+            match self.key {
+                Some(key) => chain.unlink_keyed(key, self.id()),
+                None => chain.unlink(self.id()),
+            }
+        }
+        if self.awaited {
+            drop(self.state.task.take());
+        }
     }
 }
 
 impl WaitGuard {
-    pub fn new(state: Arc<AtomicU8>) -> Self {
-        Self { state }
+    pub(crate) fn new(state: Arc<ListenerState>) -> Self {
+        Self {
+            state,
+            ticket: AtomicU64::new(0),
+            spins: 0,
+            awaited: false,
+            key: None,
+        }
+    }
+
+    /// Position of the guard's latest registration among everything registered with its event.
+    /// Tickets increase with each registration and [`Event::notify_one`](crate::Event::notify_one)
+    /// wakes the lowest ticket still waiting first (the highest with [`WakeOrder::Lifo`](crate::WakeOrder::Lifo)).
+    pub fn ticket(&self) -> u64 {
+        self.ticket.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_ticket(&self, ticket: u64) {
+        self.ticket.store(ticket, Ordering::Relaxed);
+    }
+
+    pub(crate) fn set_chain(&mut self, chain: &Arc<Chain>) {
+        // states are only pooled by the event they were registered with
+        let linked = self.state.chain.get_or_init(|| chain.clone());
+        debug_assert!(Arc::ptr_eq(linked, chain));
+    }
+
+    pub(crate) fn set_keyed(&mut self, chain: &Arc<Chain>, key: u64) {
+        self.set_chain(chain);
+        self.key = Some(key);
+    }
+
+    /// Switches the guard's registration over to waking `waker`. A guard that is still waiting but
+    /// no longer queued is registered again with its own chain. Keyed guards are only ever
+    /// switched in place, they are never queued.
+    pub(crate) fn set_async_waker(&self, waker: &core::task::Waker) {
+        let Some(chain) = self.state.chain.get() else {
+            return;
+        };
+        if let Some(key) = self.key {
+            if let Some(node) = chain
+                .keyed()
+                .get_mut(&key)
+                .and_then(|listeners| listeners.iter_mut().find(|node| node.key() == self.id()))
+            {
+                node.set_async(waker);
+            }
+            return;
+        }
+        let mut wakers = chain.lock();
+        if let Some(node) = wakers.iter_mut().find(|node| node.key() == self.id()) {
+            node.set_async(waker);
+            return;
+        }
+        // claims happen with the chain locked, still waiting here means nothing will claim it
+        if self.get_state() == State::Waiting {
+            wakers.push_back(Waker::for_guard_async(self, waker.clone()));
+            chain.len.fetch_add(1, Ordering::Release);
+        }
+    }
+
+    /// How many times [`WaitGuard::wait`] checks the state before parking.
+    pub(crate) fn set_spins(&mut self, spins: u32) {
+        self.spins = spins;
     }
 
     pub fn wait(&self) {
-        loop {
-            match self.get_state() {
-                State::Waiting => {
-                    std::thread::park();
-                }
-                _ => return,
+        for _ in 0..self.spins {
+            if get_state(&self.state) != State::Waiting {
+                break;
             }
+            core::hint::spin_loop();
         }
+        wait(&self.state);
+        acknowledge(&self.state);
+    }
+
+    /// Like [`WaitGuard::wait`] but fails with [`WaitError::Closed`] if the event was closed
+    /// rather than notified, or with [`WaitError::Full`] if the guard was never registered.
+    pub fn wait_checked(&self) -> Result<(), WaitError> {
+        self.wait();
+        self.state_to_err()
     }
 
-    pub fn wait_deadline(&self, deadline: Instant) -> Result<(), WaitError> {
-        let mut max_park_duration = Instant::now().saturating_duration_since(deadline);
-        while !max_park_duration.is_zero() {
-            match self.get_state() {
-                State::Waiting => {
-                    std::thread::park_timeout(max_park_duration);
-                    max_park_duration = deadline.saturating_duration_since(Instant::now());
+    /// [`WaitGuard::wait_checked`] without blocking, for poll based engines that can't park.
+    /// Fails with [`WaitError::WouldBlock`] while the guard is still waiting, in which case it
+    /// stays registered and can be tried again.
+    ///
+    /// ```
+    /// # use wake_me::{Event, WaitError};
+    /// let event = Event::default();
+    /// let guard = event.listen();
+    /// assert_eq!(guard.try_wait(), Err(WaitError::WouldBlock));
+    /// event.notify_one();
+    /// assert_eq!(guard.try_wait(), Ok(()));
+    /// ```
+    pub fn try_wait(&self) -> Result<(), WaitError> {
+        if self.get_state() == State::Waiting {
+            return Err(WaitError::WouldBlock);
+        }
+        acknowledge(&self.state);
+        self.state_to_err()
+    }
+
+    /// Waits until notified or `deadline` passes. Fails with [`WaitError::Timeout`] only if the
+    /// guard was still waiting at the deadline, a notification racing with it wins. Fails with
+    /// [`WaitError::Closed`] if the event was closed while waiting.
+    ///
+    /// ```
+    /// # use wake_me::{Event, State, WaitError};
+    /// # use std::time::{Duration, Instant};
+    /// let event = Event::default();
+    /// let guard = event.listen();
+    /// let deadline = Instant::now() + Duration::from_secs(1);
+    /// event.notify_one();
+    /// let waited = guard.wait_deadline(deadline).unwrap();
+    /// assert_eq!(waited.state, State::Notified);
+    /// assert!(!waited.remaining.is_zero());
+    /// let guard = event.listen();
+    /// assert_eq!(guard.wait_deadline(Instant::now()), Err(WaitError::Timeout));
+    /// ```
+    pub fn wait_deadline(&self, deadline: Instant) -> Result<Waited, WaitError> {
+        let remaining = wait_deadline(&self.state, deadline)?;
+        acknowledge(&self.state);
+        self.state_to_err()?;
+        Ok(Waited {
+            state: self.get_state(),
+            remaining,
+        })
+    }
+
+    /// Waits up to `timeout` for a notification. A notification that lands as the timeout expires
+    /// counts as [`WaitStatus::Notified`], [`WaitStatus::TimedOut`] is only returned while the
+    /// guard is still waiting, so it isn't lost and waiting again picks it up.
+    ///
+    /// ```
+    /// # use wake_me::{Event, WaitStatus};
+    /// # use std::time::Duration;
+    /// let event = Event::default();
+    /// let guard = event.listen();
+    /// assert_eq!(guard.wait_timeout(Duration::from_millis(1)), WaitStatus::TimedOut);
+    /// event.notify_one();
+    /// assert_eq!(guard.wait_timeout(Duration::from_millis(1)), WaitStatus::Notified);
+    /// ```
+    pub fn wait_timeout(&self, timeout: Duration) -> WaitStatus {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => {
+                if wait_deadline(&self.state, deadline).is_err() {
+                    return WaitStatus::TimedOut;
                 }
-                _ => return Ok(()),
+            }
+            None => wait(&self.state),
+        }
+        acknowledge(&self.state);
+        match self.get_state() {
+            State::Notified => WaitStatus::Notified,
+            State::Closed => WaitStatus::Closed,
+            State::Dropped => WaitStatus::Dropped,
+            State::Full => WaitStatus::Full,
+            State::Waiting => unreachable!("only returns once the guard stops waiting"),
+        }
+    }
+
+    pub(crate) fn state_to_err(&self) -> Result<(), WaitError> {
+        match self.get_state() {
+            State::Closed => Err(WaitError::Closed),
+            State::Full => Err(WaitError::Full),
+            _ => Ok(()),
+        }
+    }
+
+    /// [`WaitGuard::wait_deadline`] parking with exponentially growing timeouts, capped at the
+    /// time left, which smooths out the re-checks when many threads share a deadline.
+    pub fn wait_deadline_backoff(&self, deadline: Instant) -> Result<(), WaitError> {
+        wait_deadline_backoff(&self.state, deadline)?;
+        acknowledge(&self.state);
+        self.state_to_err()
+    }
+
+    /// Waits by yielding to the scheduler instead of parking, for green-thread or cooperative
+    /// runtimes where parking the OS thread would stall everything on it. The thread never sleeps
+    /// so this keeps a core busy for as long as it waits.
+    pub fn wait_yield(&self) {
+        while get_state(&self.state) == State::Waiting {
+            std::thread::yield_now();
+        }
+        acknowledge(&self.state);
+    }
+
+    /// Waits forever when `deadline` is `None`, otherwise until the deadline. Returns the state the
+    /// guard finished in.
+    pub fn wait_opt_deadline(&self, deadline: Option<Instant>) -> Result<State, WaitError> {
+        match deadline {
+            Some(deadline) => Ok(self.wait_deadline(deadline)?.state),
+            None => {
+                self.wait_checked()?;
+                Ok(self.get_state())
             }
         }
-        Err(WaitError::Timeout)
     }
 
     pub fn get_state(&self) -> State {
-        self.state.load(Ordering::Acquire).into()
+        get_state(&self.state)
+    }
+
+    /// Identifies this listener's registration, stable for as long as the guard is alive. Only
+    /// unique while the guard is alive: the event reuses a dropped guard's state, and with it the
+    /// id, for a later listener.
+    pub fn id(&self) -> usize {
+        key(&self.state)
+    }
+
+    pub(crate) fn shared_state(&self) -> &Arc<ListenerState> {
+        &self.state
+    }
+
+    /// Has whatever ends the wait also wake `task`, replacing the task registered before.
+    /// Returns whether the guard is still waiting, checked after registering so that a claim in
+    /// between either is seen here or wakes the task.
+    pub(crate) fn register_task(&mut self, task: &core::task::Waker) -> bool {
+        self.awaited = true;
+        self.state.awaited.store(true, Ordering::SeqCst);
+        self.state.task.register(task);
+        State::from(self.state.load(Ordering::SeqCst)) == State::Waiting
+    }
+
+    /// Moves a notified guard back to waiting so it can be registered again without allocating,
+    /// see [`Event::rearm`](crate::Event::rearm). Fails if the guard is still waiting or was
+    /// cancelled.
+    pub fn try_rearm(&self) -> bool {
+        let mut current = self.state.load(Ordering::Acquire);
+        while State::from(current) == State::Notified {
+            match self.state.compare_exchange_weak(
+                current,
+                State::Waiting as u8,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return true,
+                Err(actual) => current = actual,
+            }
+        }
+        false
+    }
+
+    /// Waits like [`WaitGuard::wait`] but encodes the outcome in the type of the returned guard.
+    pub fn wait_outcome(self) -> Result<CompletedGuard<Notified>, Unnotified> {
+        self.wait();
+        match self.get_state() {
+            State::Notified => Ok(CompletedGuard::new(self)),
+            State::Closed => Err(Unnotified::Closed(CompletedGuard::new(self))),
+            State::Full => Err(Unnotified::Full(CompletedGuard::new(self))),
+            _ => Err(Unnotified::Cancelled(CompletedGuard::new(self))),
+        }
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Type level marker for the final [`State`] of a [`CompletedGuard`].
+pub trait Outcome: sealed::Sealed {
+    const STATE: State;
+}
+
+/// The guard was woken by a notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Notified;
+
+/// The waker was dropped without notifying the guard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+/// The event was closed while the guard was waiting, see [`Event::close`](crate::Event::close).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Closed;
+
+/// The guard was never registered, see [`State::Full`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Full;
+
+impl sealed::Sealed for Notified {}
+impl sealed::Sealed for Cancelled {}
+impl sealed::Sealed for Closed {}
+impl sealed::Sealed for Full {}
+
+impl Outcome for Notified {
+    const STATE: State = State::Notified;
+}
+
+impl Outcome for Cancelled {
+    const STATE: State = State::Dropped;
+}
+
+impl Outcome for Closed {
+    const STATE: State = State::Closed;
+}
+
+impl Outcome for Full {
+    const STATE: State = State::Full;
+}
+
+/// The completed guard from a [`WaitGuard::wait_outcome`] that wasn't notified.
+#[derive(Debug)]
+pub enum Unnotified {
+    Cancelled(CompletedGuard<Cancelled>),
+    Closed(CompletedGuard<Closed>),
+    Full(CompletedGuard<Full>),
+}
+
+impl Unnotified {
+    pub fn outcome(&self) -> State {
+        match self {
+            Unnotified::Cancelled(guard) => guard.outcome(),
+            Unnotified::Closed(guard) => guard.outcome(),
+            Unnotified::Full(guard) => guard.outcome(),
+        }
+    }
+
+    pub fn into_inner(self) -> WaitGuard {
+        match self {
+            Unnotified::Cancelled(guard) => guard.into_inner(),
+            Unnotified::Closed(guard) => guard.into_inner(),
+            Unnotified::Full(guard) => guard.into_inner(),
+        }
+    }
+}
+
+/// A [`WaitGuard`] that has not been waited on yet, from [`Event::listen_typed`](crate::Event::listen_typed).
+/// It has no way to read the state, the outcome is only available from the [`CompletedGuard`] that
+/// [`PendingGuard::wait`] hands back, see `tests/ui`.
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct PendingGuard {
+    guard: WaitGuard,
+}
+
+impl PendingGuard {
+    pub(crate) fn new(guard: WaitGuard) -> Self {
+        Self { guard }
+    }
+
+    pub fn id(&self) -> usize {
+        self.guard.id()
+    }
+
+    /// See [`WaitGuard::wait_outcome`].
+    pub fn wait(self) -> Result<CompletedGuard<Notified>, Unnotified> {
+        self.guard.wait_outcome()
+    }
+}
+
+/// A [`WaitGuard`] that has finished waiting. Only obtainable through [`WaitGuard::wait_outcome`]
+/// or [`PendingGuard::wait`]. None of the waiting methods are available on it, see `tests/ui`.
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct CompletedGuard<S: Outcome> {
+    guard: WaitGuard,
+    _outcome: PhantomData<S>,
+}
+
+impl<S: Outcome> CompletedGuard<S> {
+    fn new(guard: WaitGuard) -> Self {
+        Self {
+            guard,
+            _outcome: PhantomData,
+        }
+    }
+
+    pub fn outcome(&self) -> State {
+        S::STATE
+    }
+
+    pub fn into_inner(self) -> WaitGuard {
+        self.guard
     }
 }
 
@@ -171,6 +1058,44 @@ impl WaitGuard {
 mod waker_tests {
     use super::*;
 
+    #[test]
+    fn try_wait() {
+        let event = crate::Event::default();
+        let guard = event.listen();
+        assert_eq!(guard.try_wait(), Err(WaitError::WouldBlock));
+        assert!(!is_acknowledged(&guard.state));
+        assert_eq!(event.listener_count(), 1);
+        event.notify_one();
+        assert_eq!(guard.try_wait(), Ok(()));
+        assert!(is_acknowledged(&guard.state));
+        let guard = event.listen();
+        event.close();
+        assert_eq!(guard.try_wait(), Err(WaitError::Closed));
+    }
+
+    #[test]
+    fn wait_timeout() {
+        let event = crate::Event::default();
+        let guard = event.listen();
+        assert_eq!(
+            guard.wait_timeout(Duration::from_millis(5)),
+            WaitStatus::TimedOut
+        );
+        assert!(!is_acknowledged(&guard.state));
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                std::thread::sleep(Duration::from_millis(5));
+                event.notify_one();
+            });
+            assert_eq!(guard.wait_timeout(Duration::MAX), WaitStatus::Notified);
+        });
+        let guard = event.listen();
+        event.close();
+        assert_eq!(guard.wait_timeout(Duration::ZERO), WaitStatus::Closed);
+        let full = crate::Event::bounded(0);
+        assert_eq!(full.listen().wait_timeout(Duration::ZERO), WaitStatus::Full);
+    }
+
     #[test]
     fn basic() {
         let (sender, recv) = std::sync::mpsc::channel();
@@ -185,17 +1110,11 @@ mod waker_tests {
                     State::Notified
                 );
             });
-            let waker = recv.recv().expect("recv failed");
-            std::thread::sleep(std::time::Duration::from_millis(100));
-            assert_eq!(
-                State::from(waker.state.load(Ordering::Relaxed)),
-                State::Waiting
-            );
+            let mut waker = recv.recv().expect("recv failed");
+            std::thread::sleep(Duration::from_millis(100));
+            assert_eq!(waker.state(), State::Waiting);
             waker.wake();
-            assert_eq!(
-                State::from(waker.state.load(Ordering::Relaxed)),
-                State::Notified
-            );
+            assert_eq!(waker.state(), State::Notified);
             jh.join().expect("join failed");
         })
     }
@@ -210,14 +1129,191 @@ mod waker_tests {
                 sender.send(waker_handle).expect("send failed");
                 drop(sleeper);
             });
-            let waker = recv.recv().expect("recv failed");
-            std::thread::sleep(std::time::Duration::from_millis(100));
+            let mut waker = recv.recv().expect("recv failed");
+            std::thread::sleep(Duration::from_millis(100));
             waker.wake();
-            assert_eq!(
-                State::from(waker.state.load(Ordering::Relaxed)),
-                State::Dropped
-            );
+            assert_eq!(waker.state(), State::Dropped);
             jh.join().expect("join failed");
         })
     }
+
+    #[test]
+    fn future_deadline_blocks() {
+        let (_waker, guard) = Waker::new();
+        let timeout = Duration::from_millis(50);
+        let start = Instant::now();
+        assert_eq!(
+            guard.wait_deadline(start + timeout),
+            Err(WaitError::Timeout)
+        );
+        // the park duration used to be computed as now - deadline, timing out straight away
+        assert!(
+            start.elapsed() >= timeout,
+            "returned after {:?}",
+            start.elapsed()
+        );
+    }
+
+    #[test]
+    fn future_deadline_waits_for_notify() {
+        let event = crate::Event::default();
+        let guard = event.listen();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                std::thread::sleep(Duration::from_millis(10));
+                event.notify_one();
+            });
+            let waited = guard
+                .wait_deadline(deadline)
+                .expect("notified before the deadline");
+            assert_eq!(waited.state, State::Notified);
+            assert!(waited.remaining > Duration::from_secs(1));
+            assert!(waited.remaining <= deadline - Instant::now() + Duration::from_millis(1));
+        });
+    }
+
+    #[test]
+    fn deadline_racing_notify() {
+        let event = &crate::Event::default();
+        for i in 0..50 {
+            let guard = event.listen();
+            let deadline = Instant::now() + Duration::from_micros(200);
+            std::thread::scope(|s| {
+                s.spawn(move || {
+                    // land on either side of the deadline
+                    std::thread::sleep(Duration::from_micros(150 + (i % 10) * 10));
+                    event.notify_one();
+                });
+                match guard.wait_deadline(deadline) {
+                    Ok(waited) => assert_eq!(waited.state, State::Notified),
+                    // a timed out guard is still registered, the late notification isn't lost
+                    Err(err) => assert_eq!(err, WaitError::Timeout),
+                }
+            });
+            assert_eq!(guard.get_state(), State::Notified);
+        }
+    }
+
+    #[test]
+    fn deadline_under_unpark_storm() {
+        let timeout = Duration::from_millis(100);
+        let done = portable_atomic::AtomicBool::new(false);
+        std::thread::scope(|s| {
+            let waiter = s.spawn(|| {
+                let (_waker, guard) = Waker::new();
+                let start = Instant::now();
+                let result = guard.wait_deadline(start + timeout);
+                let elapsed = start.elapsed();
+                done.store(true, Ordering::Release);
+                (result, elapsed)
+            });
+            let thread = waiter.thread().clone();
+            while !done.load(Ordering::Acquire) {
+                thread.unpark();
+                std::thread::sleep(Duration::from_micros(100));
+            }
+            let (result, elapsed) = waiter.join().expect("join failed");
+            assert_eq!(result, Err(WaitError::Timeout));
+            assert!(elapsed >= timeout);
+            assert!(elapsed < timeout * 3, "overslept: {elapsed:?}");
+        })
+    }
+
+    #[test]
+    fn wait_yield() {
+        let (mut waker, guard) = Waker::new();
+        std::thread::scope(|s| {
+            s.spawn(move || {
+                std::thread::sleep(Duration::from_millis(10));
+                waker.wake();
+            });
+            guard.wait_yield();
+        });
+        assert_eq!(guard.get_state(), State::Notified);
+    }
+
+    #[test]
+    fn cached_thread() {
+        assert_eq!(current_thread().id(), std::thread::current().id());
+        std::thread::spawn(|| {
+            assert_eq!(current_thread().id(), std::thread::current().id());
+        })
+        .join()
+        .expect("couldn't join!");
+
+        let event = crate::Event::default();
+        for _ in 0..3 {
+            let guard = event.listen();
+            std::thread::scope(|s| {
+                s.spawn(|| event.notify_one());
+                guard.wait();
+            });
+            assert_eq!(guard.get_state(), State::Notified);
+        }
+    }
+
+    #[test]
+    fn deadline_backoff() {
+        let (mut waker, guard) = Waker::new();
+        let deadline = Instant::now() + Duration::from_millis(20);
+        assert_eq!(
+            guard.wait_deadline_backoff(deadline),
+            Err(WaitError::Timeout)
+        );
+        assert!(Instant::now() >= deadline);
+
+        // by now the backoff is parking for far longer than it takes the wake to get through
+        let deadline = Instant::now() + Duration::from_secs(10);
+        std::thread::scope(|s| {
+            let jh = s.spawn(move || {
+                std::thread::sleep(Duration::from_millis(200));
+                let woken_at = Instant::now();
+                waker.wake();
+                woken_at
+            });
+            assert_eq!(guard.wait_deadline_backoff(deadline), Ok(()));
+            let returned_at = Instant::now();
+            let woken_at = jh.join().expect("couldn't join!");
+            assert!(returned_at.duration_since(woken_at) < Duration::from_millis(20));
+        });
+        assert_eq!(guard.get_state(), State::Notified);
+    }
+
+    #[test]
+    fn opt_deadline() {
+        let (mut waker, guard) = Waker::new();
+        let deadline = Instant::now() + Duration::from_millis(20);
+        assert_eq!(
+            guard.wait_opt_deadline(Some(deadline)),
+            Err(WaitError::Timeout)
+        );
+        assert!(Instant::now() >= deadline);
+
+        waker.wake();
+        assert_eq!(guard.wait_opt_deadline(None), Ok(State::Notified));
+    }
+
+    #[test]
+    fn outcome() {
+        let (mut waker, guard) = Waker::new();
+        waker.wake();
+        let completed = guard.wait_outcome().expect("should be notified");
+        assert_eq!(completed.outcome(), State::Notified);
+        assert_eq!(completed.into_inner().get_state(), State::Notified);
+
+        let (waker, guard) = Waker::new();
+        drop(waker);
+        let completed = guard.wait_outcome().expect_err("should be cancelled");
+        assert!(matches!(completed, Unnotified::Cancelled(_)));
+        assert_eq!(completed.outcome(), State::Dropped);
+
+        let event = crate::Event::default();
+        let guard = event.listen();
+        event.close();
+        let completed = guard.wait_outcome().expect_err("should be closed");
+        assert!(matches!(completed, Unnotified::Closed(_)));
+        assert_eq!(completed.outcome(), State::Closed);
+        assert_eq!(completed.into_inner().get_state(), State::Closed);
+    }
 }
@@ -0,0 +1,119 @@
+use crate::Event;
+use std::sync::{MutexGuard, PoisonError};
+
+impl<T> Event<T> {
+    fn latest_slot(&self) -> MutexGuard<'_, (u64, Option<T>)> {
+        self.latest.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Stores `value` as the event's latest value and wakes every listener, returning how many
+    /// were still waiting. Unlike [`Event::notify_one_with`] the value stays around, so a listener
+    /// that shows up later still sees it through [`Event::latest`], like a `watch` channel.
+    ///
+    /// ```
+    /// # use wake_me::Event;
+    /// let event = Event::<&str>::with_payload();
+    /// assert_eq!(event.latest(), None);
+    /// event.publish("v1");
+    /// let seen = event.version();
+    /// event.publish("v2");
+    /// assert_eq!(event.wait_changed(seen), Some("v2"));
+    /// ```
+    pub fn publish(&self, value: T) -> usize {
+        let mut latest = self.latest_slot();
+        latest.0 += 1;
+        latest.1 = Some(value);
+        drop(latest);
+        self.notify_all()
+    }
+
+    /// How many values have been published, pass it to [`Event::wait_changed`] to wait for the
+    /// next one.
+    pub fn version(&self) -> u64 {
+        self.latest_slot().0
+    }
+
+    /// Blocks until a value newer than `version` is published and returns the latest one. `None`
+    /// if the event was closed first.
+    pub fn wait_changed(&self, version: u64) -> Option<T>
+    where
+        T: Clone,
+    {
+        if self.wait_until(|| self.version() != version).is_err() {
+            return None;
+        }
+        self.latest()
+    }
+
+    /// Async version of [`Event::wait_changed`].
+    pub async fn changed(&self, version: u64) -> Option<T>
+    where
+        T: Clone,
+    {
+        if self
+            .wait_until_async(|| self.version() != version)
+            .await
+            .is_err()
+        {
+            return None;
+        }
+        self.latest()
+    }
+
+    /// The value last given to [`Event::publish`], if any.
+    pub fn latest(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.latest_slot().1.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::State;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn late_listener_sees_latest() {
+        let event = Event::<u32>::with_payload();
+        let guard = event.listen();
+        assert_eq!(event.publish(1), 1);
+        assert_eq!(guard.get_state(), State::Notified);
+        assert_eq!(event.publish(2), 0);
+        assert_eq!(event.latest(), Some(2));
+        assert_eq!(event.version(), 2);
+        // already behind, returns without waiting
+        assert_eq!(event.wait_changed(1), Some(2));
+        event.close();
+        assert_eq!(event.wait_changed(2), None);
+    }
+
+    #[test]
+    fn wait_changed_across_threads() {
+        let event = &Event::<String>::with_payload();
+        thread::scope(|s| {
+            let seen = event.version();
+            let jh = s.spawn(move || event.wait_changed(seen));
+            event.publish("config".to_string());
+            assert_eq!(
+                jh.join().expect("couldn't join!").as_deref(),
+                Some("config")
+            );
+        });
+    }
+
+    #[tokio::test]
+    async fn changed() {
+        let event = Arc::new(Event::<u32>::with_payload());
+        let publisher = event.clone();
+        let jh = tokio::spawn(async move {
+            publisher.wait_for_listeners_async(1).await;
+            publisher.publish(5);
+        });
+        assert_eq!(event.changed(0).await, Some(5));
+        jh.await.expect("publisher panicked");
+    }
+}
@@ -0,0 +1,50 @@
+//! Registering a pinned `ListenerNode` must not touch the heap once the chain has capacity.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::pin::pin;
+use wake_me::{Event, ListenerNode, State};
+
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+#[test]
+fn pinned_listen_does_not_allocate() {
+    let event = Event::default();
+    let mut node = pin!(ListenerNode::new());
+
+    // the first registration grows the chain
+    event.listen_pinned(node.as_mut());
+    event.notify_one();
+    node.wait();
+
+    let before = allocations();
+    for _ in 0..100 {
+        event.listen_pinned(node.as_mut());
+        event.notify_one();
+        node.wait();
+        assert_eq!(node.get_state(), State::Notified);
+    }
+    assert_eq!(allocations(), before);
+}
@@ -0,0 +1,7 @@
+//! A pending guard has no outcome to read until it has waited, and a completed guard has already
+//! waited, the type state must not let it wait again.
+
+#[test]
+fn ui() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
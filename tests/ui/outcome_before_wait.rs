@@ -0,0 +1,9 @@
+use wake_me::Event;
+
+fn main() {
+    let event = Event::default();
+    let pending = event.listen_typed();
+    event.notify_one();
+    pending.get_state();
+    pending.outcome();
+}
@@ -0,0 +1,9 @@
+use wake_me::Event;
+
+fn main() {
+    let event = Event::default();
+    let guard = event.listen();
+    event.notify_one();
+    let completed = guard.wait_outcome().unwrap();
+    completed.wait();
+}